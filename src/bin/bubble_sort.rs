@@ -1,17 +1,67 @@
-fn bubble_sort <T: Ord>(arr: &mut [T]) {
+// Counting & tracing what the sort does (see src/sort_instrumentation.rs)
+use rust_dsa::sort_instrumentation::{Instrumentation, SortEvent};
+use std::cmp::Ordering;
+
+pub fn bubble_sort <T: Ord>(arr: &mut [T]) {
+    bubble_sort_by(arr, |a, b| a.cmp(b))
+}
+
+// Only neighbours that compare Greater are swapped, so equal elements keep their order (stable)
+pub fn bubble_sort_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], compare: F) {
+    bubble_sort_observed(arr, compare, |_| ())
+}
+
+// The sort itself, telling observe about every step
+pub fn bubble_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, mut observe: O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: FnMut(SortEvent),
+{
     for i in 0..arr.len() {
+        observe(SortEvent::Pass);
         for j in 0..arr.len() - 1 - i {
-            if arr[j] > arr[j + 1] {
+            observe(SortEvent::Compare(j, j + 1));
+            if compare(&arr[j], &arr[j + 1]) == Ordering::Greater {
                 arr.swap(j, j + 1);
+                observe(SortEvent::Swap(j, j + 1));
             }
         }
     }
 }
 
+// Sort, counting comparisons, swaps & passes (a pass is one trip up the array)
+pub fn bubble_sort_instrumented<T: Ord>(arr: &mut [T], instrumentation: &mut Instrumentation) {
+    bubble_sort_instrumented_by(arr, |a, b| a.cmp(b), instrumentation)
+}
+
+pub fn bubble_sort_instrumented_by<T, F: FnMut(&T, &T) -> Ordering>(
+    arr: &mut [T],
+    compare: F,
+    instrumentation: &mut Instrumentation,
+) {
+    bubble_sort_observed(arr, compare, |event| instrumentation.record(event))
+}
+
+pub fn bubble_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut key: F) {
+    bubble_sort_by(arr, |a, b| key(a).cmp(&key(b)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn instrumented_counts() {
+        let mut arr = vec![4, 3, 2, 1];
+        let mut instrumentation = Instrumentation::new();
+        bubble_sort_instrumented(&mut arr, &mut instrumentation);
+        assert_eq!(arr, vec![1, 2, 3, 4]);
+        assert_eq!(
+            (instrumentation.comparisons, instrumentation.swaps, instrumentation.passes),
+            (6, 6, 4)
+        );
+    }
+
     #[test]
     fn descending() {
         let mut ve1 = vec![6, 5, 4, 3, 2, 1];
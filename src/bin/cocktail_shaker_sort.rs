@@ -11,7 +11,25 @@ i == 1: x = [1, 2, 3, 4]
 
 */
 
+// Counting & tracing what the sort does (see src/sort_instrumentation.rs)
+use rust_dsa::sort_instrumentation::{Instrumentation, SortEvent};
+use std::cmp::Ordering;
+
 pub fn cocktail_shaker_sort<T: Ord>(arr: &mut [T]) {
+    cocktail_shaker_sort_by(arr, |a, b| a.cmp(b))
+}
+
+// Stable like bubble sort: both directions only swap neighbours that compare Greater
+pub fn cocktail_shaker_sort_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], compare: F) {
+    cocktail_shaker_sort_observed(arr, compare, |_| ())
+}
+
+// The sort itself, telling observe about every step
+pub fn cocktail_shaker_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, mut observe: O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: FnMut(SortEvent),
+{
     // Define initial len of array
     let len = arr.len();
 
@@ -27,9 +45,12 @@ pub fn cocktail_shaker_sort<T: Ord>(arr: &mut [T]) {
         // Work up the array (0 ... len)
         // clamp "clips" value between a min and max
         // i.e. 5.clamp(0, 4) == 4 & -5.clamp(0, 4) == 0
+        observe(SortEvent::Pass);
         for i in 0..(len - 1).clamp(0, len) {
-            if arr[i] > arr[i + 1] {
+            observe(SortEvent::Compare(i, i + 1));
+            if compare(&arr[i], &arr[i + 1]) == Ordering::Greater {
                 arr.swap(i, i + 1);
+                observe(SortEvent::Swap(i, i + 1));
                 swapped = true;
             }
         }
@@ -42,9 +63,12 @@ pub fn cocktail_shaker_sort<T: Ord>(arr: &mut [T]) {
         swapped = false;
 
         // Work down the array (len ... 0)
+        observe(SortEvent::Pass);
         for i in (0..(len - 1).clamp(0, len)).rev() {
-            if arr[i] > arr[i + 1] {
+            observe(SortEvent::Compare(i, i + 1));
+            if compare(&arr[i], &arr[i + 1]) == Ordering::Greater {
                 arr.swap(i, i + 1);
+                observe(SortEvent::Swap(i, i + 1));
                 swapped = true;
             }
         }
@@ -56,11 +80,39 @@ pub fn cocktail_shaker_sort<T: Ord>(arr: &mut [T]) {
     }
 }
 
+// Sort, counting comparisons, swaps & passes (a pass is one trip up or down the array)
+pub fn cocktail_shaker_sort_instrumented<T: Ord>(arr: &mut [T], instrumentation: &mut Instrumentation) {
+    cocktail_shaker_sort_instrumented_by(arr, |a, b| a.cmp(b), instrumentation)
+}
+
+pub fn cocktail_shaker_sort_instrumented_by<T, F: FnMut(&T, &T) -> Ordering>(
+    arr: &mut [T],
+    compare: F,
+    instrumentation: &mut Instrumentation,
+) {
+    cocktail_shaker_sort_observed(arr, compare, |event| instrumentation.record(event))
+}
+
+pub fn cocktail_shaker_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut key: F) {
+    cocktail_shaker_sort_by(arr, |a, b| key(a).cmp(&key(b)))
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn instrumented_counts() {
+        let mut arr = vec![4, 3, 2, 1];
+        let mut instrumentation = Instrumentation::new();
+        cocktail_shaker_sort_instrumented(&mut arr, &mut instrumentation);
+        assert_eq!(arr, vec![1, 2, 3, 4]);
+        assert_eq!(
+            (instrumentation.comparisons, instrumentation.swaps, instrumentation.passes),
+            (12, 6, 4)
+        );
+    }
+
     #[test]
     fn basic() {
         let mut arr = vec![5, 2, 1, 3, 4, 6];
@@ -16,7 +16,25 @@ the array.
 
 */
 
-fn comb_sort<T: Ord>(arr: &mut [T]) {
+// Counting & tracing what the sort does (see src/sort_instrumentation.rs)
+use rust_dsa::sort_instrumentation::{Instrumentation, SortEvent};
+use std::cmp::Ordering;
+
+pub fn comb_sort<T: Ord>(arr: &mut [T]) {
+    comb_sort_by(arr, |a, b| a.cmp(b))
+}
+
+// Not stable: a swap across the gap can move an element past an equal one
+pub fn comb_sort_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], compare: F) {
+    comb_sort_observed(arr, compare, |_| ())
+}
+
+// The sort itself, telling observe about every step
+pub fn comb_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, mut observe: O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: FnMut(SortEvent),
+{
     // Set the initial comparison distance to the array length.
     let mut gap = arr.len();
 
@@ -37,10 +55,14 @@ fn comb_sort<T: Ord>(arr: &mut [T]) {
         }
         // If the array is not sorted, iterate up it and swap any
         // elements that are not in the correct position
-        for i in 0..arr.len() - gap {
+        // (saturating_sub stops at 0, so an empty array doesn't underflow)
+        observe(SortEvent::Pass);
+        for i in 0..arr.len().saturating_sub(gap) {
             let j = i + gap;
-            if arr[i] > arr[j] {
+            observe(SortEvent::Compare(i, j));
+            if compare(&arr[i], &arr[j]) == Ordering::Greater {
                 arr.swap(i, j);
+                observe(SortEvent::Swap(i, j));
                 sorted = false;
             }
         }
@@ -48,10 +70,39 @@ fn comb_sort<T: Ord>(arr: &mut [T]) {
 
 }
 
+// Sort, counting comparisons, swaps & passes (a pass is one trip up the array with the same gap)
+pub fn comb_sort_instrumented<T: Ord>(arr: &mut [T], instrumentation: &mut Instrumentation) {
+    comb_sort_instrumented_by(arr, |a, b| a.cmp(b), instrumentation)
+}
+
+pub fn comb_sort_instrumented_by<T, F: FnMut(&T, &T) -> Ordering>(
+    arr: &mut [T],
+    compare: F,
+    instrumentation: &mut Instrumentation,
+) {
+    comb_sort_observed(arr, compare, |event| instrumentation.record(event))
+}
+
+pub fn comb_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut key: F) {
+    comb_sort_by(arr, |a, b| key(a).cmp(&key(b)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn instrumented_counts() {
+        let mut arr = vec![4, 3, 2, 1];
+        let mut instrumentation = Instrumentation::new();
+        comb_sort_instrumented(&mut arr, &mut instrumentation);
+        assert_eq!(arr, vec![1, 2, 3, 4]);
+        assert_eq!(
+            (instrumentation.comparisons, instrumentation.swaps, instrumentation.passes),
+            (9, 2, 4)
+        );
+    }
+
     #[test]
     fn descending() {
         //descending
@@ -0,0 +1,380 @@
+/*
+
+How it works:
+
+Dominator Tree (Lengauer-Tarjan)
+
+In a directed graph with an entry node (e.g. a control-flow graph), node d dominates node n
+if every path from the entry to n passes through d. Every node dominates itself.
+
+The immediate dominator (idom) of n is the dominator closest to n (other than n itself).
+Linking every node to its immediate dominator gives the dominator tree, rooted at the entry.
+
+Consider the graph:
+
+(r, a) (r, b) (a, c) (b, c) (c, d) (d, c) (d, e) (a, e)
+
+idom(a) = r, idom(b) = r, idom(c) = r (c can be reached through a or b),
+idom(d) = c, idom(e) = r (e can be reached through a or d)
+
+Lengauer-Tarjan steps:
+
+1. Number nodes in depth first search (DFS) order from the entry, remembering each node's DFS parent.
+2. In reverse DFS order, find each node's semidominator: the node with the smallest DFS number
+   that can reach it along a path whose other nodes all have larger DFS numbers.
+   A forest (with path compression) of already processed nodes makes this fast.
+3. The semidominator is usually the immediate dominator. Where it isn't, the immediate dominator
+   is the immediate dominator of another node on the way, which is fixed up in a final pass in DFS order.
+
+The dominance frontier of d is the set of nodes where d's dominance stops:
+nodes n with a predecessor dominated by d where d doesn't strictly dominate n.
+In the example above, the frontier of a is {c, e}.
+
+*/
+
+// Use the graph types from graph.rs
+#[path = "graph.rs"]
+mod graph;
+
+use graph::{DirectedGraph, Graph, GraphError};
+use std::collections::{HashMap, HashSet};
+
+// Marks a node that has no ancestor in the forest used by Lengauer-Tarjan
+const NONE: usize = usize::MAX;
+
+// Immediate dominators of every node reachable from the entry node
+pub struct Dominators {
+    entry: String,
+    // Nodes that can't be reached from the entry (and the entry itself) aren't stored
+    idom: HashMap<String, String>,
+}
+
+impl Dominators {
+    pub fn entry(&self) -> &String {
+        &self.entry
+    }
+
+    // None for the entry node & nodes that can't be reached from it
+    pub fn immediate_dominator(&self, node: &str) -> Option<&String> {
+        self.idom.get(node)
+    }
+
+    // Every dominator of node, walking up the dominator tree from node to the entry
+    // None if node can't be reached from the entry
+    pub fn dominators(&self, node: &str) -> Option<Vec<&String>> {
+        let mut current = if node == self.entry {
+            &self.entry
+        } else {
+            self.idom.get_key_value(node)?.0
+        };
+        let mut dominators = vec![current];
+        while let Some(idom) = self.idom.get(current) {
+            dominators.push(idom);
+            current = idom;
+        }
+        Some(dominators)
+    }
+
+    // Does a dominate b?
+    pub fn dominates(&self, a: &str, b: &str) -> bool {
+        match self.dominators(b) {
+            None => false,
+            Some(dominators) => dominators.iter().any(|d| *d == a),
+        }
+    }
+
+    // A tree of edges idom(n) -> n (all with weight 1) rooted at the entry node
+    pub fn dominator_tree(&self) -> DirectedGraph {
+        let mut tree = DirectedGraph::new();
+        tree.add_node(&self.entry);
+        let mut edges: Vec<(&String, &String)> = self.idom.iter().collect();
+        edges.sort();
+        for (node, idom) in edges {
+            tree.add_edge((idom.as_str(), node.as_str(), 1));
+        }
+        tree
+    }
+
+    // The dominance frontier of every node reachable from the entry
+    // (using the method of Cooper, Harvey & Kennedy)
+    pub fn dominance_frontiers(&self, graph: &DirectedGraph) -> HashMap<String, HashSet<String>> {
+        let mut frontiers: HashMap<String, HashSet<String>> = HashMap::new();
+        frontiers.insert(self.entry.to_string(), HashSet::new());
+        for node in self.idom.keys() {
+            frontiers.insert(node.to_string(), HashSet::new());
+        }
+
+        let predecessors = reachable_predecessors(graph, &frontiers);
+        for (node, node_predecessors) in &predecessors {
+            // Only join points (nodes with several ways in) can be in a frontier, except the entry,
+            // which is a join point with a single edge back to it (it also has the way in from outside)
+            if node_predecessors.len() < 2 && self.idom.contains_key(*node) {
+                continue;
+            }
+            let idom = self.idom.get(*node);
+            for predecessor in node_predecessors {
+                // Walk up from the predecessor until reaching node's immediate dominator
+                let mut runner = Some(*predecessor);
+                while runner.is_some() && runner != idom {
+                    let r = runner.unwrap();
+                    frontiers.get_mut(r).unwrap().insert(node.to_string());
+                    runner = self.idom.get(r);
+                }
+            }
+        }
+        frontiers
+    }
+}
+
+// Distinct predecessors of each node, only counting nodes in `reachable`
+fn reachable_predecessors<'a, T>(
+    graph: &'a DirectedGraph,
+    reachable: &HashMap<String, T>,
+) -> HashMap<&'a String, Vec<&'a String>> {
+    let mut predecessors: HashMap<&String, Vec<&String>> = HashMap::new();
+    for (from_node, to_node, _) in graph.edges() {
+        if !reachable.contains_key(from_node) || !reachable.contains_key(to_node) {
+            continue;
+        }
+        let entry = predecessors.entry(to_node).or_default();
+        if !entry.contains(&from_node) {
+            entry.push(from_node);
+        }
+    }
+    predecessors
+}
+
+// Path compression on the forest: point v (and every node above it) at the root of its tree
+// while keeping label[v] as the node with the smallest semidominator on the way
+fn compress(ancestor: &mut [usize], label: &mut [usize], semi: &[usize], v: usize) {
+    // Collect the path up to (but not including) the root's child
+    let mut path = Vec::new();
+    let mut current = v;
+    while ancestor[ancestor[current]] != NONE {
+        path.push(current);
+        current = ancestor[current];
+    }
+    // Work back down the path, top first
+    for &node in path.iter().rev() {
+        let a = ancestor[node];
+        if semi[label[a]] < semi[label[node]] {
+            label[node] = label[a];
+        }
+        ancestor[node] = ancestor[a];
+    }
+}
+
+// The node with the smallest semidominator on the forest path above v
+fn eval(ancestor: &mut [usize], label: &mut [usize], semi: &[usize], v: usize) -> usize {
+    if ancestor[v] == NONE {
+        return v;
+    }
+    compress(ancestor, label, semi, v);
+    label[v]
+}
+
+pub fn lengauer_tarjan(graph: &DirectedGraph, entry: &str) -> Result<Dominators, GraphError> {
+    if !graph.contains(entry) {
+        return Err(GraphError::NodeNotInGraph(entry.to_string()));
+    }
+
+    // 1. Depth first search, numbering nodes 0, 1, 2... in the order they are found
+    // From here on nodes are referred to by their DFS number
+    let mut number: HashMap<&str, usize> = HashMap::new();
+    let mut vertex: Vec<&String> = Vec::new();
+    let mut parent: Vec<usize> = Vec::new();
+
+    let (entry_node, _) = graph.adjacency_table().get_key_value(entry).unwrap();
+    let mut stack: Vec<(&String, usize)> = vec![(entry_node, NONE)];
+    while let Some((node, node_parent)) = stack.pop() {
+        if number.contains_key(node.as_str()) {
+            continue;
+        }
+        let n = vertex.len();
+        number.insert(node, n);
+        vertex.push(node);
+        parent.push(node_parent);
+        // Push in reverse so that neighbours are visited in the order they were added
+        for (neighbour, _) in graph.neighbours(node).unwrap().iter().rev() {
+            if !number.contains_key(neighbour.as_str()) {
+                stack.push((neighbour, n));
+            }
+        }
+    }
+
+    let node_count = vertex.len();
+    let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+    for (from_node, to_node, _) in graph.edges() {
+        if let (Some(&v), Some(&w)) = (number.get(from_node.as_str()), number.get(to_node.as_str())) {
+            predecessors[w].push(v);
+        }
+    }
+
+    // 2. Semidominators, in reverse DFS order
+    let mut semi: Vec<usize> = (0..node_count).collect();
+    let mut idom: Vec<usize> = vec![NONE; node_count];
+    let mut ancestor: Vec<usize> = vec![NONE; node_count];
+    let mut label: Vec<usize> = (0..node_count).collect();
+    let mut bucket: Vec<Vec<usize>> = vec![Vec::new(); node_count];
+
+    for w in (1..node_count).rev() {
+        for &v in &predecessors[w] {
+            let u = eval(&mut ancestor, &mut label, &semi, v);
+            if semi[u] < semi[w] {
+                semi[w] = semi[u];
+            }
+        }
+        bucket[semi[w]].push(w);
+
+        // Add w to the forest under its DFS parent
+        let p = parent[w];
+        ancestor[w] = p;
+
+        // Every node whose semidominator is p now has an (implicit) immediate dominator
+        for v in std::mem::take(&mut bucket[p]) {
+            let u = eval(&mut ancestor, &mut label, &semi, v);
+            idom[v] = if semi[u] < semi[v] { u } else { p };
+        }
+    }
+
+    // 3. Fix up the implicit immediate dominators, in DFS order
+    for w in 1..node_count {
+        if idom[w] != semi[w] {
+            idom[w] = idom[idom[w]];
+        }
+    }
+
+    Ok(Dominators {
+        entry: entry.to_string(),
+        idom: (1..node_count)
+            .map(|w| (vertex[w].to_string(), vertex[idom[w]].to_string()))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example_graph() -> DirectedGraph {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("r", "a", 1));
+        graph.add_edge(("r", "b", 1));
+        graph.add_edge(("a", "c", 1));
+        graph.add_edge(("b", "c", 1));
+        graph.add_edge(("c", "d", 1));
+        graph.add_edge(("d", "c", 1));
+        graph.add_edge(("d", "e", 1));
+        graph.add_edge(("a", "e", 1));
+        graph
+    }
+
+    fn set(nodes: &[&str]) -> HashSet<String> {
+        nodes.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn test_immediate_dominators() {
+        let graph = example_graph();
+        let dominators = lengauer_tarjan(&graph, "r").unwrap();
+
+        assert_eq!(dominators.immediate_dominator("r"), None);
+        assert_eq!(dominators.immediate_dominator("a").unwrap(), "r");
+        assert_eq!(dominators.immediate_dominator("b").unwrap(), "r");
+        assert_eq!(dominators.immediate_dominator("c").unwrap(), "r");
+        assert_eq!(dominators.immediate_dominator("d").unwrap(), "c");
+        assert_eq!(dominators.immediate_dominator("e").unwrap(), "r");
+
+        assert_eq!(
+            dominators.dominators("d").unwrap(),
+            vec![&String::from("d"), &String::from("c"), &String::from("r")]
+        );
+        assert!(dominators.dominates("c", "d"));
+        assert!(dominators.dominates("d", "d"));
+        assert!(!dominators.dominates("a", "c"));
+    }
+
+    #[test]
+    fn test_lengauer_tarjan_paper_example() {
+        // The example graph from Lengauer & Tarjan (1979)
+        let mut graph = DirectedGraph::new();
+        let edges = [
+            ("R", "A"), ("R", "B"), ("R", "C"), ("A", "D"), ("B", "A"), ("B", "D"),
+            ("B", "E"), ("C", "F"), ("C", "G"), ("D", "L"), ("E", "H"), ("F", "I"),
+            ("G", "I"), ("G", "J"), ("H", "E"), ("H", "K"), ("I", "K"), ("J", "I"),
+            ("K", "I"), ("K", "R"), ("L", "H"),
+        ];
+        for (from_node, to_node) in edges.iter() {
+            graph.add_edge((from_node, to_node, 1));
+        }
+
+        let dominators = lengauer_tarjan(&graph, "R").unwrap();
+
+        let expected = [
+            ("A", "R"), ("B", "R"), ("C", "R"), ("D", "R"), ("E", "R"), ("F", "C"),
+            ("G", "C"), ("H", "R"), ("I", "R"), ("J", "G"), ("K", "R"), ("L", "D"),
+        ];
+        for (node, idom) in expected.iter() {
+            assert_eq!(dominators.immediate_dominator(node).unwrap(), idom);
+        }
+    }
+
+    #[test]
+    fn test_dominator_tree() {
+        let graph = example_graph();
+        let tree = lengauer_tarjan(&graph, "r").unwrap().dominator_tree();
+
+        assert_eq!(tree.edges().len(), 5);
+        assert_eq!(
+            tree.neighbours("r").unwrap(),
+            &vec![
+                (String::from("a"), 1),
+                (String::from("b"), 1),
+                (String::from("c"), 1),
+                (String::from("e"), 1)
+            ]
+        );
+        assert_eq!(tree.neighbours("c").unwrap(), &vec![(String::from("d"), 1)]);
+    }
+
+    #[test]
+    fn test_dominance_frontiers() {
+        let graph = example_graph();
+        let frontiers = lengauer_tarjan(&graph, "r").unwrap().dominance_frontiers(&graph);
+
+        assert_eq!(frontiers["r"], set(&[]));
+        assert_eq!(frontiers["a"], set(&["c", "e"]));
+        assert_eq!(frontiers["b"], set(&["c"]));
+        assert_eq!(frontiers["c"], set(&["c", "e"]));
+        assert_eq!(frontiers["d"], set(&["c", "e"]));
+        assert_eq!(frontiers["e"], set(&[]));
+    }
+
+    #[test]
+    fn test_loop_back_to_entry() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "a", 1));
+
+        let frontiers = lengauer_tarjan(&graph, "a").unwrap().dominance_frontiers(&graph);
+
+        assert_eq!(frontiers["a"], set(&["a"]));
+        assert_eq!(frontiers["b"], set(&["a"]));
+    }
+
+    #[test]
+    fn test_unreachable_nodes() {
+        let mut graph = example_graph();
+        graph.add_edge(("x", "a", 1));
+
+        let dominators = lengauer_tarjan(&graph, "r").unwrap();
+
+        assert_eq!(dominators.immediate_dominator("x"), None);
+        assert_eq!(dominators.dominators("x"), None);
+        assert!(!dominators.dominance_frontiers(&graph).contains_key("x"));
+        assert_eq!(
+            lengauer_tarjan(&graph, "z").err(),
+            Some(GraphError::NodeNotInGraph(String::from("z")))
+        );
+    }
+}
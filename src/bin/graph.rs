@@ -6,20 +6,127 @@ How it works:
 
 // HashMap stores a Hash Table of keys (hashes) & values
 // HashSet stores a set of unique hashes
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::error::Error;
 use std::fmt;
 
+// Only compiled with `cargo build --features serde`
+#[cfg(feature = "serde")]
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
 // Debug creates an automatic interface for printing any type
 // Clone explicitly duplicates an object (instead of Copy which implicitly duplicates)
 // i.e. Clone creates an entirely new memory location & pointer and copies data to that location
 // While Copy (a bitwise copy) copies only a memory address
-#[derive(Debug, Clone)]
-pub struct NodeNotInGraph;
+// PartialEq allows errors to be compared with == (e.g. in tests)
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphError {
+    // Accessing a node (named here) that is not in the graph
+    NodeNotInGraph(String),
+    // An algorithm that needs non-negative weights found this edge
+    NegativeWeight { from: String, to: String, weight: i32 },
+    // A cycle whose total weight is negative, so shortest paths are undefined
+    NegativeCycle,
+    // An algorithm that needs a directed acyclic graph found a cycle
+    NotADag,
+    // Some node can't be reached from another
+    Disconnected,
+    // The graph (or list of edges) has a cycle, or is disconnected
+    NotATree,
+    // The graph has no nodes
+    EmptyGraph,
+    // An exponential time algorithm was given more nodes than it allows
+    TooManyNodes { nodes: usize, limit: usize },
+    // An algorithm that needs an undirected graph was given a directed one
+    NotUndirected,
+    // Delta-stepping was given a bucket width (delta) below 1
+    InvalidDelta(i64),
+    // A graph given as numbered vertices (e.g. a list of edges) with a negative number of vertices
+    NegativeVertexCount(i64),
+    // A vertex (of a graph given as numbered vertices) outside 0..number_of_vertices
+    VertexOutOfRange { vertex: i64, number_of_vertices: i64 },
+    // The item (e.g. edge) at this position in a list has a vertex outside 0..number_of_vertices
+    ItemVertexOutOfRange { position: usize, vertex: i64, number_of_vertices: i64 },
+}
 
-// Define a display mechanism for the NodeNotInGraph object to allow output of error message
-impl fmt::Display for NodeNotInGraph {
+// Define a display mechanism for the GraphError object to allow output of error message
+impl fmt::Display for GraphError {
     fn fmt (&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "accessing node that is not in the graph!")
+        match self {
+            GraphError::NodeNotInGraph(node) => write!(f, "node {} is not in the graph", node),
+            GraphError::NegativeWeight { from, to, weight } => {
+                write!(f, "edge {} -> {} has negative weight {}", from, to, weight)
+            }
+            GraphError::NegativeCycle => write!(f, "the graph has a negative cycle"),
+            GraphError::NotADag => write!(f, "the graph has a cycle (it is not a DAG)"),
+            GraphError::Disconnected => write!(f, "the graph is not connected"),
+            GraphError::NotATree => write!(f, "the graph is not a tree"),
+            GraphError::EmptyGraph => write!(f, "the graph has no nodes"),
+            GraphError::TooManyNodes { nodes, limit } => {
+                write!(f, "the graph has {} nodes (the limit is {})", nodes, limit)
+            }
+            GraphError::NotUndirected => write!(f, "the graph is directed (an undirected graph is needed)"),
+            GraphError::InvalidDelta(delta) => write!(f, "delta must be at least 1 (got {})", delta),
+            GraphError::NegativeVertexCount(n) => {
+                write!(f, "number of vertices must not be negative (got {})", n)
+            }
+            GraphError::VertexOutOfRange { vertex, number_of_vertices } => {
+                write!(f, "vertex {} is outside 0..{}", vertex, number_of_vertices)
+            }
+            GraphError::ItemVertexOutOfRange { position, vertex, number_of_vertices } => write!(
+                f,
+                "item {} has vertex {} outside 0..{}",
+                position, vertex, number_of_vertices
+            ),
+        }
+    }
+}
+
+// Error only needs Debug & Display (which are defined above), so the body is empty
+impl Error for GraphError {}
+
+/*
+What add_edge does when the graph already has an edge between the two nodes
+
+AllowParallel: store another (parallel) edge alongside the existing one
+ReplaceWeight: overwrite the weight of the existing edge
+KeepMinWeight: keep whichever of the two weights is smaller
+Reject: leave the existing edge alone & ignore the new one
+
+Copy is fine here because the enum is only a tag (no heap data to duplicate)
+The default (as used by new()) is AllowParallel
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum EdgePolicy {
+    #[default]
+    AllowParallel,
+    ReplaceWeight,
+    KeepMinWeight,
+    Reject,
+}
+
+// Add to_node (with weight) to a list of neighbours following the edge policy
+// Returns true if the list changed
+fn insert_neighbour(neighbours: &mut Vec<(String, i32)>, to_node: &str, weight: i32, policy: EdgePolicy) -> bool {
+    let existing = neighbours.iter_mut().find(|(n, _)| n == to_node);
+    match (existing, policy) {
+        (None, _) | (Some(_), EdgePolicy::AllowParallel) => {
+            neighbours.push((to_node.to_string(), weight));
+            true
+        }
+        (Some((_, w)), EdgePolicy::ReplaceWeight) => {
+            let changed = *w != weight;
+            *w = weight;
+            changed
+        }
+        (Some((_, w)), EdgePolicy::KeepMinWeight) => {
+            let changed = weight < *w;
+            *w = (*w).min(weight);
+            changed
+        }
+        (Some(_), EdgePolicy::Reject) => false,
     }
 }
 
@@ -37,16 +144,22 @@ i.e.
 */
 pub struct DirectedGraph {
     adjacency_table: HashMap<String, Vec<(String, i32)>>,
+    policy: EdgePolicy,
 }
 
 impl Graph for DirectedGraph {
     // Define new() method & signify that new() returns a DirectedGraph object
+    // Parallel edges are allowed unless a policy is chosen with with_policy()
     fn new() -> DirectedGraph {
+        DirectedGraph::with_policy(EdgePolicy::AllowParallel)
+    }
 
+    fn with_policy(policy: EdgePolicy) -> DirectedGraph {
         // Create a DirectedGraph object by initialising a new HashMap (see defined type above)
         // i.e. DirectedGraph is implemented as a HashMap with a defined structure
         DirectedGraph {
             adjacency_table: HashMap::new(),
+            policy,
         }
     }
 
@@ -62,19 +175,79 @@ impl Graph for DirectedGraph {
         &self.adjacency_table
     }
 
+    fn is_directed(&self) -> bool {
+        true
+    }
+
+    fn edge_policy(&self) -> EdgePolicy {
+        self.policy
+    }
+
+}
+
+impl DirectedGraph {
+    // Flip the direction of every edge (also called the transpose of the graph)
+    // i.e. a -> b becomes b -> a
+    pub fn reverse(&self) -> DirectedGraph {
+        let mut graph = DirectedGraph::with_policy(self.policy);
+        for node in self.nodes() {
+            graph.add_node(node);
+        }
+        for (from_node, to_node, weight) in self.edges() {
+            graph.add_edge((to_node.as_str(), from_node.as_str(), weight));
+        }
+        graph
+    }
+
+    // Forget the direction of every edge
+    // If both a -> b and b -> a exist they become a single undirected edge
+    // carrying the smaller of the two weights
+    pub fn to_undirected(&self) -> UndirectedGraph {
+        let mut weights: HashMap<(&String, &String), i32> = HashMap::new();
+        for (from_node, to_node, weight) in self.edges() {
+            // Store each pair once, with the "smaller" node name first
+            let key = if from_node <= to_node {
+                (from_node, to_node)
+            } else {
+                (to_node, from_node)
+            };
+            weights
+                .entry(key)
+                .and_modify(|w| *w = (*w).min(weight))
+                .or_insert(weight);
+        }
+
+        let mut graph = UndirectedGraph::with_policy(self.policy);
+        for node in self.nodes() {
+            graph.add_node(node);
+        }
+        // Sort the pairs so that neighbour lists are built in a predictable order
+        let mut pairs: Vec<_> = weights.into_iter().collect();
+        pairs.sort();
+        for ((a, b), weight) in pairs {
+            graph.add_edge((a.as_str(), b.as_str(), weight));
+        }
+        graph
+    }
 }
 
 
 // Define structure of undirected graph
 pub struct UndirectedGraph {
-    adjacency_table: HashMap<String, Vec<(String, i32)>>
+    adjacency_table: HashMap<String, Vec<(String, i32)>>,
+    policy: EdgePolicy,
 }
 
 // Means that we are implementing the Graph interface for the UndirectedGraph type
 impl Graph for UndirectedGraph {
     fn new() -> UndirectedGraph {
+        UndirectedGraph::with_policy(EdgePolicy::AllowParallel)
+    }
+
+    fn with_policy(policy: EdgePolicy) -> UndirectedGraph {
         UndirectedGraph {
             adjacency_table: HashMap::new(),
+            policy,
         }
     }
 
@@ -86,25 +259,258 @@ impl Graph for UndirectedGraph {
         &self.adjacency_table
     }
 
+    fn is_directed(&self) -> bool {
+        false
+    }
+
+    fn edge_policy(&self) -> EdgePolicy {
+        self.policy
+    }
+
     // Does this add_edge method overlay the Graph add_edge method (below)?
     // Yes
-    fn add_edge(&mut self, edge: (&str, &str, i32)) {
-        self.add_node(edge.0);
-        self.add_node(edge.1);
+    fn add_edge(&mut self, edge: (&str, &str, i32)) -> bool {
+        let policy = self.policy;
+        add_undirected_edge(self, edge, policy)
+    }
+}
+
+// Shared by UndirectedGraph & MultiGraph
+fn add_undirected_edge<G: Graph>(graph: &mut G, edge: (&str, &str, i32), policy: EdgePolicy) -> bool {
+    graph.add_node(edge.0);
+    graph.add_node(edge.1);
+
+    // Insert both nodes in the adjacency table as connected to one another with weight edge.2
+    // Note that because the graph is undirected, if a -> b, b -> a.
+    // Whereas a directed graph can have, a -> b without b -> a
+    let table = graph.adjacency_table_mutable();
+    let changed = insert_neighbour(table.get_mut(edge.0).unwrap(), edge.1, edge.2, policy);
+
+    // A self-loop (a, a) is only stored once
+    // Both lists always hold the same edges, so the policy makes the same choice for the other half
+    if edge.0 != edge.1 {
+        insert_neighbour(table.get_mut(edge.1).unwrap(), edge.0, edge.2, policy);
+    }
+    changed
+}
+
+
+/*
+Define structure of an undirected multigraph
+
+A multigraph expects parallel edges (several edges between the same pair of nodes,
+e.g. several roads between two towns) so it always uses EdgePolicy::AllowParallel
+& has methods for working with the parallel edges between two nodes
+*/
+pub struct MultiGraph {
+    adjacency_table: HashMap<String, Vec<(String, i32)>>,
+}
+
+impl Graph for MultiGraph {
+    fn new() -> MultiGraph {
+        MultiGraph {
+            adjacency_table: HashMap::new(),
+        }
+    }
+
+    // A multigraph always allows parallel edges, so AllowParallel is the only policy it accepts
+    // (this is what the graph transformations pass, as edge_policy() is always AllowParallel)
+    // Panics for any other policy, use UndirectedGraph::with_policy to merge parallel edges
+    fn with_policy(policy: EdgePolicy) -> MultiGraph {
+        assert_eq!(
+            policy,
+            EdgePolicy::AllowParallel,
+            "a MultiGraph always allows parallel edges"
+        );
+        MultiGraph::new()
+    }
+
+    fn adjacency_table_mutable(&mut self) -> &mut HashMap<String, Vec<(String, i32)>> {
+        &mut self.adjacency_table
+    }
+
+    fn adjacency_table(&self) -> &HashMap<String, Vec<(String, i32)>> {
+        &self.adjacency_table
+    }
+
+    fn is_directed(&self) -> bool {
+        false
+    }
+
+    fn edge_policy(&self) -> EdgePolicy {
+        EdgePolicy::AllowParallel
+    }
+
+    fn add_edge(&mut self, edge: (&str, &str, i32)) -> bool {
+        add_undirected_edge(self, edge, EdgePolicy::AllowParallel)
+    }
+}
+
+impl MultiGraph {
+    // Weights of every edge between a & b
+    pub fn parallel_edges(&self, a: &str, b: &str) -> Result<Vec<i32>, GraphError> {
+        if !self.contains(b) {
+            return Err(GraphError::NodeNotInGraph(b.to_string()));
+        }
+        Ok(self
+            .neighbours(a)?
+            .iter()
+            .filter(|(n, _)| n == b)
+            .map(|(_, weight)| *weight)
+            .collect())
+    }
+
+    // Number of edges between a & b
+    pub fn edge_multiplicity(&self, a: &str, b: &str) -> Result<usize, GraphError> {
+        Ok(self.parallel_edges(a, b)?.len())
+    }
+
+    // Remove one edge between a & b with the given weight
+    // Returns false if there is no such edge
+    pub fn remove_edge(&mut self, edge: (&str, &str, i32)) -> bool {
+        let (a, b, weight) = edge;
+        let position = match self.adjacency_table.get(a) {
+            None => return false,
+            Some(neighbours) => neighbours.iter().position(|(n, w)| n == b && *w == weight),
+        };
+        let i = match position {
+            None => return false,
+            Some(i) => i,
+        };
+        self.adjacency_table.get_mut(a).unwrap().remove(i);
+        if a != b {
+            let neighbours = self.adjacency_table.get_mut(b).unwrap();
+            let j = neighbours.iter().position(|(n, w)| n == a && *w == weight).unwrap();
+            neighbours.remove(j);
+        }
+        true
+    }
 
-        // Insert both nodes in the adjacency table as connected to one another with weight edge.2
-        // Note that because the graph is undirected, if a -> b, b -> a.
-        // Whereas a directed graph can have, a -> b without b -> a
-        self.adjacency_table
-            .entry(edge.0.to_string())
-            .and_modify(|e| {
-                e.push((edge.1.to_string(), edge.2));
-            });
-        self.adjacency_table
-            .entry(edge.1.to_string())
-            .and_modify(|e| {
-                e.push((edge.0.to_string(), edge.2));
-            });
+    // Collapse parallel edges into single edges, following the given policy
+    // (e.g. EdgePolicy::KeepMinWeight keeps the cheapest edge between each pair of nodes)
+    pub fn to_simple(&self, policy: EdgePolicy) -> UndirectedGraph {
+        let mut graph = UndirectedGraph::with_policy(policy);
+        for node in self.nodes() {
+            graph.add_node(node);
+        }
+        let mut edges = self.edges();
+        // Each edge is listed in both directions, keep one copy of each
+        edges.retain(|(from_node, to_node, _)| from_node <= to_node);
+        edges.sort();
+        for (from_node, to_node, weight) in edges {
+            graph.add_edge((from_node.as_str(), to_node.as_str(), weight));
+        }
+        graph
+    }
+}
+
+
+/*
+Serialization (with the `serde` feature)
+
+Graphs are written as a list of nodes & a list of edges, e.g. in JSON:
+
+{
+  "nodes": ["a", "b", "c"],
+  "edges": [{"from": "a", "to": "b", "weight": 5}, {"from": "b", "to": "c", "weight": 10}],
+  "policy": "AllowParallel"
+}
+
+Nodes are sorted by name so the same graph is always written the same way.
+An undirected edge is only written once (with the "smaller" node as "from").
+Reading a graph back adds each edge with add_edge, so parallel edges & self-loops survive the round trip.
+"policy" may be left out when reading, the graph then allows parallel edges.
+*/
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct GraphData {
+    nodes: Vec<String>,
+    edges: Vec<EdgeData>,
+    #[serde(default)]
+    policy: EdgePolicy,
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct EdgeData {
+    from: String,
+    to: String,
+    weight: i32,
+}
+
+#[cfg(feature = "serde")]
+impl GraphData {
+    fn from_graph<G: Graph>(graph: &G) -> GraphData {
+        let mut nodes: Vec<&String> = graph.nodes().into_iter().collect();
+        nodes.sort();
+
+        let mut edges = Vec::new();
+        for from_node in &nodes {
+            for (to_node, weight) in graph.neighbours(from_node).unwrap() {
+                if !graph.is_directed() && *from_node > to_node {
+                    continue;
+                }
+                edges.push(EdgeData {
+                    from: from_node.to_string(),
+                    to: to_node.to_string(),
+                    weight: *weight,
+                });
+            }
+        }
+
+        GraphData {
+            nodes: nodes.into_iter().cloned().collect(),
+            edges,
+            policy: graph.edge_policy(),
+        }
+    }
+
+    // Fill an empty graph, failing if an edge mentions a node missing from the nodes list
+    fn into_graph<G: Graph>(self, mut graph: G) -> Result<G, GraphError> {
+        for node in &self.nodes {
+            graph.add_node(node);
+        }
+        for edge in &self.edges {
+            for node in [&edge.from, &edge.to].iter() {
+                if !graph.contains(node) {
+                    return Err(GraphError::NodeNotInGraph(node.to_string()));
+                }
+            }
+            graph.add_edge((&edge.from, &edge.to, edge.weight));
+        }
+        Ok(graph)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for DirectedGraph {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GraphData::from_graph(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for DirectedGraph {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = GraphData::deserialize(deserializer)?;
+        let graph = DirectedGraph::with_policy(data.policy);
+        data.into_graph(graph).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for UndirectedGraph {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GraphData::from_graph(self).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for UndirectedGraph {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = GraphData::deserialize(deserializer)?;
+        let graph = UndirectedGraph::with_policy(data.policy);
+        data.into_graph(graph).map_err(de::Error::custom)
     }
 }
 
@@ -130,6 +536,10 @@ impl Example {
 
 */
 
+// Cost of the cheapest path to a node & the node (with the weight of the edge) it was reached from
+// (None for the node the paths start from)
+pub type Route<'a> = (i64, Option<(&'a String, i32)>);
+
 /*
 
 `trait` defines methods that are shared between implementations of a type (i.e. a shared interface)
@@ -140,8 +550,12 @@ Here, Graph defines the shared functionality between DirectedGraph and Undirecte
 pub trait Graph {
     // define "slots" for implementations of directed or undirected graph
     fn new() -> Self;
+    // An empty graph that handles parallel edges with the given policy
+    fn with_policy(policy: EdgePolicy) -> Self;
     fn adjacency_table_mutable(&mut self) -> &mut HashMap<String, Vec<(String, i32)>>;
     fn adjacency_table(&self) -> &HashMap<String, Vec<(String, i32)>>;
+    fn is_directed(&self) -> bool;
+    fn edge_policy(&self) -> EdgePolicy;
 
     fn add_node(&mut self, node: &str) -> bool {
         // get node from adjacency table - .get() is defined for HashMap
@@ -156,23 +570,28 @@ pub trait Graph {
         }
     }
 
-    fn add_edge(&mut self, edge: (&str, &str, i32)) {
+    // Returns true if the graph changed (false if the edge policy ignored the edge)
+    fn add_edge(&mut self, edge: (&str, &str, i32)) -> bool {
+        self.add_neighbour(edge)
+    }
+
+    // Add only the from -> to entry of the adjacency table, following the edge policy
+    // For an undirected graph this is one half of an edge (add_edge adds both halves)
+    fn add_neighbour(&mut self, edge: (&str, &str, i32)) -> bool {
         self.add_node(edge.0);
         self.add_node(edge.1);
 
         // adjacency_table_mutable() points to adjacency table of the Graph object so that it can be changed
-        self.adjacency_table_mutable()
-            .entry(edge.0.to_string())
-            .and_modify(|e| {
-                e.push((edge.1.to_string(), edge.2));
-            });
+        let policy = self.edge_policy();
+        let neighbours = self.adjacency_table_mutable().get_mut(edge.0).unwrap();
+        insert_neighbour(neighbours, edge.1, edge.2, policy)
     }
 
     // Simply return contents of the adjacency table (HashMap) for the given node
     // Else (if node doesn't exist), raise error
-    fn neighbours(&self, node: &str) -> Result<&Vec<(String, i32)>, NodeNotInGraph> {
+    fn neighbours(&self, node: &str) -> Result<&Vec<(String, i32)>, GraphError> {
         match self.adjacency_table().get(node) {
-            None => Err(NodeNotInGraph),
+            None => Err(GraphError::NodeNotInGraph(node.to_string())),
             Some(i) => Ok(i),
         }
     }
@@ -197,11 +616,404 @@ pub trait Graph {
         }
         edges
     }
+
+    /*
+    Graph metrics
+
+    Distances here count the number of edges (hops) on a path & ignore weights,
+    found with a breadth first search (BFS) from each node.
+
+    In a directed graph a path must follow edge directions, so metrics built on
+    eccentricity are only defined when every node can reach every other node
+    (the graph is strongly connected). Those metrics return GraphError::Disconnected otherwise.
+    */
+
+    // Number of hops from source to every node it can reach (including itself, at 0)
+    fn shortest_path_lengths(&self, source: &str) -> Result<HashMap<&String, usize>, GraphError> {
+        let (source, _) = match self.adjacency_table().get_key_value(source) {
+            None => return Err(GraphError::NodeNotInGraph(source.to_string())),
+            Some(entry) => entry,
+        };
+
+        let mut distances: HashMap<&String, usize> = HashMap::new();
+        distances.insert(source, 0);
+
+        // Visit nodes in the order they were discovered (first in, first out)
+        let mut queue: VecDeque<&String> = VecDeque::new();
+        queue.push_back(source);
+        while let Some(node) = queue.pop_front() {
+            let distance = distances[node];
+            for (neighbour, _) in &self.adjacency_table()[node] {
+                if !distances.contains_key(neighbour) {
+                    distances.insert(neighbour, distance + 1);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        Ok(distances)
+    }
+
+    // Cheapest total weight from source to every node it can reach (Dijkstra's algorithm)
+    // Unlike the hop counts above this uses edge weights, which must not be negative
+    fn shortest_path_costs(&self, source: &str) -> Result<HashMap<&String, i64>, GraphError> {
+        let tree = self.shortest_path_tree(source)?;
+        Ok(tree.into_iter().map(|(node, (cost, _))| (node, cost)).collect())
+    }
+
+    // Dijkstra's algorithm, also keeping the route to every node it can reach
+    // Following the previous nodes back from a node gives a cheapest path from source
+    fn shortest_path_tree(&self, source: &str) -> Result<HashMap<&String, Route<'_>>, GraphError> {
+        let (source, _) = match self.adjacency_table().get_key_value(source) {
+            None => return Err(GraphError::NodeNotInGraph(source.to_string())),
+            Some(entry) => entry,
+        };
+        for (from_node, to_node, weight) in self.edges() {
+            if weight < 0 {
+                return Err(GraphError::NegativeWeight {
+                    from: from_node.to_string(),
+                    to: to_node.to_string(),
+                    weight,
+                });
+            }
+        }
+
+        let mut routes: HashMap<&String, Route> = HashMap::new();
+        routes.insert(source, (0, None));
+
+        // BinaryHeap is a max heap, so wrap entries in Reverse to visit the cheapest node first
+        let mut heap = BinaryHeap::new();
+        heap.push(Reverse((0, source)));
+        while let Some(Reverse((cost, node))) = heap.pop() {
+            // A cheaper route to this node has already been handled
+            if cost > routes[node].0 {
+                continue;
+            }
+            for (neighbour, weight) in &self.adjacency_table()[node] {
+                let new_cost = cost + *weight as i64;
+                if routes.get(neighbour).is_none_or(|(c, _)| new_cost < *c) {
+                    routes.insert(neighbour, (new_cost, Some((node, *weight))));
+                    heap.push(Reverse((new_cost, neighbour)));
+                }
+            }
+        }
+        Ok(routes)
+    }
+
+    // The eccentricity of a node is the distance to the node furthest away from it
+    fn eccentricities(&self) -> Result<HashMap<&String, usize>, GraphError> {
+        let node_count = self.nodes().len();
+        if node_count == 0 {
+            return Err(GraphError::EmptyGraph);
+        }
+        let mut eccentricities = HashMap::new();
+        for node in self.nodes() {
+            let distances = self.shortest_path_lengths(node).unwrap();
+            // Some node can't be reached, so the distance to it is infinite
+            if distances.len() < node_count {
+                return Err(GraphError::Disconnected);
+            }
+            eccentricities.insert(node, *distances.values().max().unwrap());
+        }
+        Ok(eccentricities)
+    }
+
+    // Largest eccentricity (the longest shortest path in the graph)
+    fn diameter(&self) -> Result<usize, GraphError> {
+        self.eccentricities().map(|e| *e.values().max().unwrap())
+    }
+
+    // Smallest eccentricity
+    fn radius(&self) -> Result<usize, GraphError> {
+        self.eccentricities().map(|e| *e.values().min().unwrap())
+    }
+
+    // Nodes whose eccentricity == radius (sorted by name)
+    fn center(&self) -> Result<Vec<&String>, GraphError> {
+        let eccentricities = self.eccentricities()?;
+        let radius = *eccentricities.values().min().unwrap();
+        let mut center: Vec<&String> = eccentricities
+            .into_iter()
+            .filter(|(_, e)| *e == radius)
+            .map(|(node, _)| node)
+            .collect();
+        center.sort();
+        Ok(center)
+    }
+
+    // Nodes whose eccentricity == diameter (sorted by name)
+    fn periphery(&self) -> Result<Vec<&String>, GraphError> {
+        let eccentricities = self.eccentricities()?;
+        let diameter = *eccentricities.values().max().unwrap();
+        let mut periphery: Vec<&String> = eccentricities
+            .into_iter()
+            .filter(|(_, e)| *e == diameter)
+            .map(|(node, _)| node)
+            .collect();
+        periphery.sort();
+        Ok(periphery)
+    }
+
+    // Mean distance over every ordered pair of distinct nodes (0 for a single node)
+    fn average_shortest_path_length(&self) -> Result<f64, GraphError> {
+        let node_count = self.nodes().len();
+        match node_count {
+            0 => return Err(GraphError::EmptyGraph),
+            1 => return Ok(0.0),
+            _ => {}
+        }
+        let mut total = 0;
+        for node in self.nodes() {
+            let distances = self.shortest_path_lengths(node).unwrap();
+            if distances.len() < node_count {
+                return Err(GraphError::Disconnected);
+            }
+            total += distances.values().sum::<usize>();
+        }
+        Ok(total as f64 / (node_count * (node_count - 1)) as f64)
+    }
+
+    // Fraction of all possible edges that are in the graph (between 0 & 1)
+    // An undirected edge is stored twice (a -> b, b -> a) & there are n * (n - 1)
+    // possible ordered pairs, so the same formula works for both types of graph
+    // Parallel edges & self-loops are ignored
+    fn density(&self) -> f64 {
+        let node_count = self.nodes().len();
+        if node_count < 2 {
+            return 0.0;
+        }
+        let pairs: HashSet<(&String, &String)> = self
+            .edges()
+            .into_iter()
+            .filter(|(from_node, to_node, _)| from_node != to_node)
+            .map(|(from_node, to_node, _)| (from_node, to_node))
+            .collect();
+        pairs.len() as f64 / (node_count * (node_count - 1)) as f64
+    }
+
+    // Count the (ordered) pairs of neighbours of node that are linked to one another
+    // & the number of pairs that could be linked
+    // Used by both the local & global clustering coefficients below
+    fn neighbour_links(&self, node: &str) -> Result<(usize, usize), GraphError> {
+        let neighbours: HashSet<&String> = self
+            .neighbours(node)?
+            .iter()
+            .map(|(n, _)| n)
+            .filter(|n| n.as_str() != node)
+            .collect();
+
+        let mut links = 0;
+        for neighbour in &neighbours {
+            let linked: HashSet<&String> = self.adjacency_table()[*neighbour]
+                .iter()
+                .map(|(n, _)| n)
+                .filter(|n| n != neighbour && neighbours.contains(n))
+                .collect();
+            links += linked.len();
+        }
+        let k = neighbours.len();
+        Ok((links, k * k.saturating_sub(1)))
+    }
+
+    // Local clustering coefficient: the fraction of a node's neighbours that are
+    // neighbours of each other (i.e. how close they are to forming a complete graph)
+    // Nodes with fewer than 2 neighbours have a coefficient of 0
+    fn clustering(&self, node: &str) -> Result<f64, GraphError> {
+        let (links, possible) = self.neighbour_links(node)?;
+        if possible == 0 {
+            return Ok(0.0);
+        }
+        Ok(links as f64 / possible as f64)
+    }
+
+    // Mean of the local clustering coefficients of every node
+    fn average_clustering(&self) -> f64 {
+        let nodes = self.nodes();
+        if nodes.is_empty() {
+            return 0.0;
+        }
+        let total: f64 = nodes.iter().map(|n| self.clustering(n).unwrap()).sum();
+        total / nodes.len() as f64
+    }
+
+    // Global clustering coefficient (transitivity): links between neighbours
+    // summed over all nodes, divided by all possible links
+    // For an undirected graph this is 3 * triangles / connected triples
+    fn transitivity(&self) -> f64 {
+        let mut links = 0;
+        let mut possible = 0;
+        for node in self.nodes() {
+            let (l, p) = self.neighbour_links(node).unwrap();
+            links += l;
+            possible += p;
+        }
+        if possible == 0 {
+            return 0.0;
+        }
+        links as f64 / possible as f64
+    }
+
+    /*
+    Graph transformations
+
+    Each of these builds a brand new graph (of the same type & edge policy as self) by copying
+    entries of the adjacency table with add_neighbour, so they work for both directed & undirected
+    graphs & the new graph follows its edge policy (e.g. a union of two Reject graphs that both
+    have a -> b keeps only one a -> b).
+    Note that an undirected edge is stored in the adjacency table twice (a -> b and b -> a)
+    so copying entries one by one keeps both halves of the edge together. Both halves are copied
+    in the same order, so the policy makes the same choice for each half.
+
+    `where Self: Sized` is needed because the methods return Self by value
+    */
+
+    // Keep only the given nodes & the edges running between them
+    fn induced_subgraph(&self, nodes: &[&str]) -> Result<Self, GraphError>
+    where
+        Self: Sized,
+    {
+        let mut graph = Self::with_policy(self.edge_policy());
+        for node in nodes {
+            if !self.contains(node) {
+                return Err(GraphError::NodeNotInGraph(node.to_string()));
+            }
+            graph.add_node(node);
+        }
+        for (from_node, from_node_neighbours) in self.adjacency_table() {
+            if !graph.contains(from_node) {
+                continue;
+            }
+            for (to_node, weight) in from_node_neighbours {
+                if graph.contains(to_node) {
+                    graph.add_neighbour((from_node, to_node, *weight));
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    // Keep only the given (from, to) edges & the nodes they touch
+    // In an undirected graph (a, b) keeps both a -> b and b -> a
+    // Every parallel copy of a listed edge is kept
+    fn edge_subgraph(&self, edges: &[(&str, &str)]) -> Result<Self, GraphError>
+    where
+        Self: Sized,
+    {
+        let mut keep: HashSet<(&str, &str)> = HashSet::new();
+        for (from_node, to_node) in edges {
+            for node in [from_node, to_node].iter() {
+                if !self.contains(node) {
+                    return Err(GraphError::NodeNotInGraph(node.to_string()));
+                }
+            }
+            keep.insert((from_node, to_node));
+            if !self.is_directed() {
+                keep.insert((to_node, from_node));
+            }
+        }
+
+        let mut graph = Self::with_policy(self.edge_policy());
+        for (from_node, to_node) in &keep {
+            graph.add_node(from_node);
+            graph.add_node(to_node);
+        }
+        for (from_node, from_node_neighbours) in self.adjacency_table() {
+            for (to_node, weight) in from_node_neighbours {
+                if keep.contains(&(from_node.as_str(), to_node.as_str())) {
+                    graph.add_neighbour((from_node, to_node, *weight));
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    // All nodes & edges of both graphs
+    // An edge with the same endpoints & weight in both graphs is only kept once
+    fn union(&self, other: &Self) -> Self
+    where
+        Self: Sized,
+    {
+        let mut graph = Self::with_policy(self.edge_policy());
+        for (from_node, from_node_neighbours) in self.adjacency_table() {
+            graph.add_node(from_node);
+            for (to_node, weight) in from_node_neighbours {
+                graph.add_neighbour((from_node, to_node, *weight));
+            }
+        }
+        for (from_node, from_node_neighbours) in other.adjacency_table() {
+            graph.add_node(from_node);
+            for (to_node, weight) in from_node_neighbours {
+                // Skip edges that self already has
+                let in_self = match self.adjacency_table().get(from_node) {
+                    None => false,
+                    Some(n) => n.contains(&(to_node.to_string(), *weight)),
+                };
+                if !in_self {
+                    graph.add_neighbour((from_node, to_node, *weight));
+                }
+            }
+        }
+        graph
+    }
+
+    // Nodes found in both graphs & edges (with the same weight) found in both graphs
+    fn intersection(&self, other: &Self) -> Self
+    where
+        Self: Sized,
+    {
+        let mut graph = Self::with_policy(self.edge_policy());
+        for node in self.nodes() {
+            if other.contains(node) {
+                graph.add_node(node);
+            }
+        }
+        for (from_node, from_node_neighbours) in self.adjacency_table() {
+            let other_neighbours = match other.adjacency_table().get(from_node) {
+                None => continue,
+                Some(n) => n,
+            };
+            // Match each edge in `other` at most once so parallel edges are counted properly
+            let mut unmatched: Vec<&(String, i32)> = other_neighbours.iter().collect();
+            for edge in from_node_neighbours {
+                if let Some(i) = unmatched.iter().position(|e| *e == edge) {
+                    unmatched.remove(i);
+                    graph.add_neighbour((from_node, &edge.0, edge.1));
+                }
+            }
+        }
+        graph
+    }
+
+    // Same nodes, with an edge (of the given weight) wherever self has no edge
+    // Self-loops are never added
+    fn complement(&self, weight: i32) -> Self
+    where
+        Self: Sized,
+    {
+        let mut nodes: Vec<&String> = self.nodes().into_iter().collect();
+        nodes.sort();
+
+        let mut graph = Self::with_policy(self.edge_policy());
+        for node in &nodes {
+            graph.add_node(node);
+        }
+        for from_node in &nodes {
+            let neighbours = &self.adjacency_table()[*from_node];
+            for to_node in &nodes {
+                if from_node == to_node || neighbours.iter().any(|(n, _)| n == *to_node) {
+                    continue;
+                }
+                graph.add_neighbour((from_node, to_node, weight));
+            }
+        }
+        graph
+    }
 }
 
 #[cfg(test)]
 mod test_undirected_graph {
+    use super::EdgePolicy;
     use super::Graph;
+    use super::GraphError;
     use super::UndirectedGraph;
 
     #[test]
@@ -241,12 +1053,162 @@ mod test_undirected_graph {
 
     }
 
+    #[test]
+    fn test_self_loop() {
+        let mut graph = UndirectedGraph::new();
+
+        graph.add_edge(("a", "a", 5));
+
+        assert_eq!(graph.neighbours("a").unwrap(), &vec![(String::from("a"), 5)]);
+    }
+
+    #[test]
+    fn test_edge_policy() {
+        let mut graph = UndirectedGraph::with_policy(EdgePolicy::KeepMinWeight);
+
+        assert!(graph.add_edge(("a", "b", 5)));
+        assert!(!graph.add_edge(("b", "a", 7)));
+        assert!(graph.add_edge(("b", "a", 3)));
+
+        // Both halves of the edge are updated
+        assert_eq!(graph.neighbours("a").unwrap(), &vec![(String::from("b"), 3)]);
+        assert_eq!(graph.neighbours("b").unwrap(), &vec![(String::from("a"), 3)]);
+
+        let mut graph = UndirectedGraph::with_policy(EdgePolicy::Reject);
+
+        assert!(graph.add_edge(("a", "b", 5)));
+        assert!(!graph.add_edge(("b", "a", 3)));
+        assert_eq!(graph.edges().len(), 2);
+    }
+
+    #[test]
+    fn test_induced_subgraph() {
+        let mut graph = UndirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("c", "a", 7));
+
+        let subgraph = graph.induced_subgraph(&["a", "b"]).unwrap();
+
+        assert_eq!(subgraph.nodes().len(), 2);
+        assert_eq!(subgraph.edges().len(), 2);
+        assert_eq!(subgraph.neighbours("a").unwrap(), &vec![(String::from("b"), 5)]);
+        assert_eq!(subgraph.neighbours("b").unwrap(), &vec![(String::from("a"), 5)]);
+        assert_eq!(
+            graph.induced_subgraph(&["a", "d"]).err(),
+            Some(GraphError::NodeNotInGraph(String::from("d")))
+        );
+    }
+
+    #[test]
+    fn test_edge_subgraph() {
+        let mut graph = UndirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("c", "a", 7));
+
+        // The order of an undirected edge doesn't matter
+        let subgraph = graph.edge_subgraph(&[("c", "b")]).unwrap();
+
+        assert!(!subgraph.contains("a"));
+        assert_eq!(subgraph.neighbours("b").unwrap(), &vec![(String::from("c"), 10)]);
+        assert_eq!(subgraph.neighbours("c").unwrap(), &vec![(String::from("b"), 10)]);
+    }
+
+    #[test]
+    fn test_distance_metrics() {
+        // A path a - b - c - d
+        let mut graph = UndirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("c", "d", 7));
+
+        let eccentricities = graph.eccentricities().unwrap();
+        assert_eq!(eccentricities[&String::from("a")], 3);
+        assert_eq!(eccentricities[&String::from("b")], 2);
+
+        assert_eq!(graph.diameter(), Ok(3));
+        assert_eq!(graph.radius(), Ok(2));
+        assert_eq!(graph.center(), Ok(vec![&String::from("b"), &String::from("c")]));
+        assert_eq!(graph.periphery(), Ok(vec![&String::from("a"), &String::from("d")]));
+        // (1 + 2 + 3 + 1 + 1 + 2) * 2 / 12
+        assert_eq!(graph.average_shortest_path_length(), Ok(20.0 / 12.0));
+
+        // Disconnected graphs have no finite diameter
+        graph.add_node("e");
+        assert_eq!(graph.diameter(), Err(GraphError::Disconnected));
+        assert_eq!(graph.center(), Err(GraphError::Disconnected));
+        assert_eq!(graph.average_shortest_path_length(), Err(GraphError::Disconnected));
+
+        assert_eq!(UndirectedGraph::new().radius(), Err(GraphError::EmptyGraph));
+    }
+
+    #[test]
+    fn test_density_and_clustering() {
+        // A triangle a, b, c with a tail c - d
+        let mut graph = UndirectedGraph::new();
+
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "c", 1));
+        graph.add_edge(("c", "a", 1));
+        graph.add_edge(("c", "d", 1));
+
+        assert_eq!(graph.density(), 4.0 / 6.0);
+
+        assert_eq!(graph.clustering("a").unwrap(), 1.0);
+        assert_eq!(graph.clustering("c").unwrap(), 1.0 / 3.0);
+        assert_eq!(graph.clustering("d").unwrap(), 0.0);
+        assert_eq!(graph.clustering("e"), Err(GraphError::NodeNotInGraph(String::from("e"))));
+
+        // Nodes are visited in any order, so allow for floating point rounding
+        let expected = (1.0 + 1.0 + 1.0 / 3.0) / 4.0;
+        assert!((graph.average_clustering() - expected).abs() < 1e-12);
+        // 1 triangle & 5 connected triples
+        assert_eq!(graph.transitivity(), 3.0 / 5.0);
+    }
+
+    #[test]
+    fn test_complement() {
+        let mut graph = UndirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_node("d");
+
+        let complement = graph.complement(1);
+
+        // 4 nodes have 6 possible edges, 2 of which are in the graph
+        assert_eq!(complement.edges().len(), 2 * 4);
+        assert_eq!(complement.neighbours("a").unwrap(), &vec![(String::from("c"), 1), (String::from("d"), 1)]);
+        assert_eq!(complement.neighbours("b").unwrap(), &vec![(String::from("d"), 1)]);
+    }
+
+    #[test]
+    fn test_union_follows_edge_policy() {
+        let mut graph = UndirectedGraph::with_policy(EdgePolicy::ReplaceWeight);
+        graph.add_edge(("a", "b", 1));
+        let mut other = UndirectedGraph::with_policy(EdgePolicy::ReplaceWeight);
+        other.add_edge(("a", "b", 2));
+        other.add_edge(("b", "c", 3));
+
+        // Both halves of a - b take the weight from other instead of becoming parallel edges
+        let union = graph.union(&other);
+        assert_eq!(union.edges().len(), 2 * 2);
+        assert_eq!(union.neighbours("a").unwrap(), &vec![(String::from("b"), 2)]);
+        assert_eq!(union.neighbours("b").unwrap(), &vec![(String::from("a"), 2), (String::from("c"), 3)]);
+    }
+
 }
 
 
 #[cfg(test)]
 mod test_directed_graph {
+    use super::EdgePolicy;
     use super::Graph;
+    use super::GraphError;
     use super::DirectedGraph;
 
     #[test]
@@ -284,6 +1246,81 @@ mod test_directed_graph {
 
     }
 
+    #[test]
+    fn test_parallel_edges() {
+        let mut graph = DirectedGraph::new();
+
+        assert!(graph.add_edge(("a", "b", 5)));
+        assert!(graph.add_edge(("a", "b", 5)));
+
+        assert_eq!(graph.edge_policy(), EdgePolicy::AllowParallel);
+        assert_eq!(
+            graph.neighbours("a").unwrap(),
+            &vec![(String::from("b"), 5), (String::from("b"), 5)]
+        );
+    }
+
+    #[test]
+    fn test_edge_policy() {
+        let mut graph = DirectedGraph::with_policy(EdgePolicy::ReplaceWeight);
+        graph.add_edge(("a", "b", 5));
+        assert!(graph.add_edge(("a", "b", 7)));
+        assert!(!graph.add_edge(("a", "b", 7)));
+        // b -> a is a different edge in a directed graph
+        assert!(graph.add_edge(("b", "a", 1)));
+        assert_eq!(graph.neighbours("a").unwrap(), &vec![(String::from("b"), 7)]);
+
+        let mut graph = DirectedGraph::with_policy(EdgePolicy::KeepMinWeight);
+        graph.add_edge(("a", "b", 5));
+        assert!(!graph.add_edge(("a", "b", 7)));
+        assert!(graph.add_edge(("a", "b", 2)));
+        assert_eq!(graph.neighbours("a").unwrap(), &vec![(String::from("b"), 2)]);
+
+        let mut graph = DirectedGraph::with_policy(EdgePolicy::Reject);
+        graph.add_edge(("a", "b", 5));
+        assert!(!graph.add_edge(("a", "b", 2)));
+        assert_eq!(graph.neighbours("a").unwrap(), &vec![(String::from("b"), 5)]);
+
+        // The policy carries over to transformed graphs
+        assert_eq!(graph.reverse().edge_policy(), EdgePolicy::Reject);
+        assert_eq!(graph.to_undirected().edge_policy(), EdgePolicy::Reject);
+        assert_eq!(graph.induced_subgraph(&["a"]).unwrap().edge_policy(), EdgePolicy::Reject);
+        assert_eq!(graph.edge_subgraph(&[("a", "b")]).unwrap().edge_policy(), EdgePolicy::Reject);
+        assert_eq!(graph.union(&graph.reverse()).edge_policy(), EdgePolicy::Reject);
+        assert_eq!(graph.intersection(&graph.reverse()).edge_policy(), EdgePolicy::Reject);
+
+        // & keeps working on them, e.g. a lower weight a -> b is ignored
+        let mut complement = graph.complement(4).complement(9);
+        assert_eq!(complement.edge_policy(), EdgePolicy::Reject);
+        assert!(!complement.add_edge(("a", "b", 1)));
+        assert_eq!(complement.neighbours("a").unwrap(), &vec![(String::from("b"), 9)]);
+    }
+
+    #[test]
+    fn test_union_applies_edge_policy() {
+        let union_of = |policy: EdgePolicy| {
+            let mut graph = DirectedGraph::with_policy(policy);
+            graph.add_edge(("a", "b", 5));
+            let mut other = DirectedGraph::with_policy(policy);
+            other.add_edge(("a", "b", 2));
+            other.add_edge(("b", "c", 1));
+            graph.union(&other)
+        };
+
+        // Reject keeps the a -> b from self & drops the one from other
+        let union = union_of(EdgePolicy::Reject);
+        assert_eq!(union.edge_policy(), EdgePolicy::Reject);
+        assert_eq!(union.neighbours("a").unwrap(), &vec![(String::from("b"), 5)]);
+        assert_eq!(union.neighbours("b").unwrap(), &vec![(String::from("c"), 1)]);
+
+        let union = union_of(EdgePolicy::KeepMinWeight);
+        assert_eq!(union.neighbours("a").unwrap(), &vec![(String::from("b"), 2)]);
+
+        // Parallel edges are still kept when they are allowed
+        let union = union_of(EdgePolicy::AllowParallel);
+        assert_eq!(union.neighbours("a").unwrap(), &vec![(String::from("b"), 5), (String::from("b"), 2)]);
+    }
+
     #[test]
     fn test_neighbors() {
         let mut graph = DirectedGraph::new();
@@ -298,6 +1335,38 @@ mod test_directed_graph {
         );
     }
 
+    #[test]
+    fn test_errors() {
+        let graph = DirectedGraph::new();
+
+        assert_eq!(graph.neighbours("a"), Err(GraphError::NodeNotInGraph(String::from("a"))));
+
+        let messages = [
+            (GraphError::NodeNotInGraph(String::from("a")), "node a is not in the graph"),
+            (
+                GraphError::NegativeWeight { from: String::from("a"), to: String::from("b"), weight: -1 },
+                "edge a -> b has negative weight -1",
+            ),
+            (GraphError::NegativeCycle, "the graph has a negative cycle"),
+            (GraphError::NotADag, "the graph has a cycle (it is not a DAG)"),
+            (GraphError::Disconnected, "the graph is not connected"),
+            (GraphError::NotATree, "the graph is not a tree"),
+            (GraphError::EmptyGraph, "the graph has no nodes"),
+            (GraphError::TooManyNodes { nodes: 30, limit: 20 }, "the graph has 30 nodes (the limit is 20)"),
+            (GraphError::NotUndirected, "the graph is directed (an undirected graph is needed)"),
+            (GraphError::InvalidDelta(0), "delta must be at least 1 (got 0)"),
+            (GraphError::NegativeVertexCount(-3), "number of vertices must not be negative (got -3)"),
+            (GraphError::VertexOutOfRange { vertex: 7, number_of_vertices: 3 }, "vertex 7 is outside 0..3"),
+            (
+                GraphError::ItemVertexOutOfRange { position: 4, vertex: 9, number_of_vertices: 5 },
+                "item 4 has vertex 9 outside 0..5",
+            ),
+        ];
+        for (error, message) in messages.iter() {
+            assert_eq!(error.to_string(), *message);
+        }
+    }
+
     #[test]
     fn test_contains() {
         let mut graph = DirectedGraph::new();
@@ -313,4 +1382,290 @@ mod test_directed_graph {
 
     }
 
+    #[test]
+    fn test_reverse() {
+        let mut graph = DirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_node("d");
+
+        let reversed = graph.reverse();
+
+        assert_eq!(reversed.nodes().len(), 4);
+        assert_eq!(reversed.neighbours("a").unwrap(), &vec![]);
+        assert_eq!(reversed.neighbours("b").unwrap(), &vec![(String::from("a"), 5)]);
+        assert_eq!(reversed.neighbours("c").unwrap(), &vec![(String::from("b"), 10)]);
+    }
+
+    #[test]
+    fn test_to_undirected() {
+        let mut graph = DirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "a", 3));
+        graph.add_edge(("b", "c", 10));
+
+        let undirected = graph.to_undirected();
+
+        // a <-> b collapses into a single edge with the smaller weight
+        assert_eq!(undirected.neighbours("a").unwrap(), &vec![(String::from("b"), 3)]);
+        assert_eq!(
+            undirected.neighbours("b").unwrap(),
+            &vec![(String::from("a"), 3), (String::from("c"), 10)]
+        );
+        assert_eq!(undirected.neighbours("c").unwrap(), &vec![(String::from("b"), 10)]);
+    }
+
+    #[test]
+    fn test_union_and_intersection() {
+        let mut graph_a = DirectedGraph::new();
+        graph_a.add_edge(("a", "b", 5));
+        graph_a.add_edge(("b", "c", 10));
+
+        let mut graph_b = DirectedGraph::new();
+        graph_b.add_edge(("a", "b", 5));
+        graph_b.add_edge(("b", "c", 1));
+        graph_b.add_edge(("c", "d", 2));
+
+        let union = graph_a.union(&graph_b);
+        assert_eq!(union.nodes().len(), 4);
+        assert_eq!(union.neighbours("a").unwrap(), &vec![(String::from("b"), 5)]);
+        assert_eq!(
+            union.neighbours("b").unwrap(),
+            &vec![(String::from("c"), 10), (String::from("c"), 1)]
+        );
+        assert_eq!(union.neighbours("c").unwrap(), &vec![(String::from("d"), 2)]);
+
+        let intersection = graph_a.intersection(&graph_b);
+        assert_eq!(intersection.nodes().len(), 3);
+        assert_eq!(intersection.edges(), vec![(&String::from("a"), &String::from("b"), 5)]);
+    }
+
+    #[test]
+    fn test_distance_metrics() {
+        // A directed cycle a -> b -> c -> a
+        let mut graph = DirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("c", "a", 7));
+
+        let distances = graph.shortest_path_lengths("a").unwrap();
+        assert_eq!(distances[&String::from("c")], 2);
+        assert_eq!(graph.diameter(), Ok(2));
+        assert_eq!(graph.radius(), Ok(2));
+        assert_eq!(graph.density(), 3.0 / 6.0);
+
+        // b can no longer reach a, so the graph isn't strongly connected
+        graph.adjacency_table_mutable().get_mut("c").unwrap().clear();
+        assert_eq!(graph.eccentricities(), Err(GraphError::Disconnected));
+    }
+
+    #[test]
+    fn test_shortest_path_costs() {
+        let mut graph = DirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        // A route with more hops but a lower cost
+        graph.add_edge(("a", "d", 1));
+        graph.add_edge(("d", "e", 1));
+        graph.add_edge(("e", "c", 1));
+        graph.add_node("f");
+
+        let costs = graph.shortest_path_costs("a").unwrap();
+        assert_eq!(costs[&String::from("c")], 3);
+        assert_eq!(costs[&String::from("b")], 5);
+        // f can't be reached
+        assert_eq!(costs.len(), 5);
+        assert_eq!(graph.shortest_path_lengths("a").unwrap()[&String::from("c")], 2);
+
+        // The route to c goes a -> d -> e -> c
+        let tree = graph.shortest_path_tree("a").unwrap();
+        assert_eq!(tree[&String::from("a")], (0, None));
+        assert_eq!(tree[&String::from("c")], (3, Some((&String::from("e"), 1))));
+        assert_eq!(tree[&String::from("e")], (2, Some((&String::from("d"), 1))));
+        assert_eq!(tree[&String::from("d")], (1, Some((&String::from("a"), 1))));
+
+        graph.add_edge(("e", "a", -1));
+        assert!(graph.shortest_path_tree("a").is_err());
+        assert_eq!(
+            graph.shortest_path_costs("a"),
+            Err(GraphError::NegativeWeight { from: String::from("e"), to: String::from("a"), weight: -1 })
+        );
+    }
+
+    #[test]
+    fn test_complement() {
+        let mut graph = DirectedGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+
+        let complement = graph.complement(1);
+
+        assert_eq!(complement.neighbours("a").unwrap(), &vec![(String::from("c"), 1)]);
+        assert_eq!(complement.neighbours("b").unwrap(), &vec![(String::from("a"), 1)]);
+        assert_eq!(
+            complement.neighbours("c").unwrap(),
+            &vec![(String::from("a"), 1), (String::from("b"), 1)]
+        );
+    }
+
+}
+
+
+#[cfg(test)]
+mod test_multigraph {
+    use super::EdgePolicy;
+    use super::Graph;
+    use super::MultiGraph;
+
+    #[test]
+    fn test_parallel_edges() {
+        let mut graph = MultiGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "a", 3));
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 1));
+
+        assert_eq!(graph.edge_multiplicity("a", "b").unwrap(), 3);
+        assert_eq!(graph.edge_multiplicity("b", "a").unwrap(), 3);
+        assert_eq!(graph.edge_multiplicity("a", "c").unwrap(), 0);
+        assert_eq!(graph.parallel_edges("b", "a").unwrap(), vec![5, 3, 5]);
+        assert!(graph.parallel_edges("a", "z").is_err());
+    }
+
+    #[test]
+    fn test_with_policy() {
+        let mut graph = MultiGraph::with_policy(EdgePolicy::AllowParallel);
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("a", "b", 5));
+        assert_eq!(graph.edge_multiplicity("a", "b").unwrap(), 2);
+
+        // Transformations build their result with the graph's own policy
+        let union = graph.union(&graph);
+        assert_eq!(union.edge_policy(), EdgePolicy::AllowParallel);
+        assert_eq!(union.edge_multiplicity("a", "b").unwrap(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "a MultiGraph always allows parallel edges")]
+    fn test_with_policy_rejects_other_policies() {
+        MultiGraph::with_policy(EdgePolicy::Reject);
+    }
+
+    #[test]
+    fn test_remove_edge() {
+        let mut graph = MultiGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("a", "b", 3));
+        graph.add_edge(("a", "a", 1));
+
+        assert!(graph.remove_edge(("b", "a", 5)));
+        assert!(!graph.remove_edge(("b", "a", 5)));
+        assert_eq!(graph.parallel_edges("a", "b").unwrap(), vec![3]);
+        assert_eq!(graph.parallel_edges("b", "a").unwrap(), vec![3]);
+
+        assert!(graph.remove_edge(("a", "a", 1)));
+        assert_eq!(graph.edge_multiplicity("a", "a").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_to_simple() {
+        let mut graph = MultiGraph::new();
+
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "a", 3));
+        graph.add_edge(("b", "c", 1));
+
+        let simple = graph.to_simple(EdgePolicy::KeepMinWeight);
+
+        assert_eq!(simple.neighbours("a").unwrap(), &vec![(String::from("b"), 3)]);
+        assert_eq!(simple.edges().len(), 4);
+    }
+}
+
+
+#[cfg(all(test, feature = "serde"))]
+mod test_serde {
+    use super::DirectedGraph;
+    use super::EdgePolicy;
+    use super::Graph;
+    use super::UndirectedGraph;
+
+    // Edges sorted so that graphs can be compared regardless of neighbour order
+    fn sorted_edges<G: Graph>(graph: &G) -> Vec<(&String, &String, i32)> {
+        let mut edges = graph.edges();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_directed_json() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("a", "b", 5));
+        graph.add_node("d");
+
+        let json = serde_json::to_string(&graph).unwrap();
+        assert_eq!(
+            json,
+            r#"{"nodes":["a","b","c","d"],"edges":[{"from":"a","to":"b","weight":5},{"from":"b","to":"c","weight":10}],"policy":"AllowParallel"}"#
+        );
+
+        let read: DirectedGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.nodes(), graph.nodes());
+        assert_eq!(sorted_edges(&read), sorted_edges(&graph));
+    }
+
+    #[test]
+    fn test_undirected_round_trip() {
+        let mut graph = UndirectedGraph::with_policy(EdgePolicy::AllowParallel);
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "a", 3));
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("c", "c", 1));
+
+        let json = serde_json::to_string(&graph).unwrap();
+        let read: UndirectedGraph = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(read.nodes(), graph.nodes());
+        assert_eq!(sorted_edges(&read), sorted_edges(&graph));
+
+        let mut graph = UndirectedGraph::with_policy(EdgePolicy::Reject);
+        graph.add_edge(("a", "b", 5));
+        let json = serde_json::to_string(&graph).unwrap();
+        let read: UndirectedGraph = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.edge_policy(), EdgePolicy::Reject);
+    }
+
+    #[test]
+    fn test_missing_policy() {
+        let json = r#"{"nodes":["a","b"],"edges":[{"from":"a","to":"b","weight":5}]}"#;
+
+        let read: DirectedGraph = serde_json::from_str(json).unwrap();
+        assert_eq!(read.edge_policy(), EdgePolicy::AllowParallel);
+        assert_eq!(read.neighbours("a").unwrap(), &vec![(String::from("b"), 5)]);
+
+        // Writing it back adds the policy
+        let written = serde_json::to_string(&read).unwrap();
+        assert_eq!(
+            written,
+            r#"{"nodes":["a","b"],"edges":[{"from":"a","to":"b","weight":5}],"policy":"AllowParallel"}"#
+        );
+        let again: DirectedGraph = serde_json::from_str(&written).unwrap();
+        assert_eq!(sorted_edges(&again), sorted_edges(&read));
+    }
+
+    #[test]
+    fn test_unknown_node() {
+        let json = r#"{"nodes":["a"],"edges":[{"from":"a","to":"b","weight":5}],"policy":"AllowParallel"}"#;
+
+        let error = serde_json::from_str::<DirectedGraph>(json).err().unwrap();
+        assert!(error.to_string().starts_with("node b is not in the graph"));
+    }
 }
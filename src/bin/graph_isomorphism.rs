@@ -0,0 +1,510 @@
+/*
+
+How it works:
+
+Graph Isomorphism & Subgraph Isomorphism (VF2)
+
+Two graphs are isomorphic if the nodes of one can be renamed to give exactly the other graph.
+A pattern graph is (induced) subgraph isomorphic to a target graph if some set of the target's nodes,
+with all of the edges between them, is isomorphic to the pattern.
+
+For example, the triangle (x, y) (y, z) (z, x) is found 6 times in the graph (a, b) (b, c) (c, a) (c, d):
+{x: a, y: b, z: c}, {x: a, y: c, z: b}, {x: b, y: a, z: c}... (every ordering of a, b & c)
+
+VF2 builds the mapping one pair of nodes at a time, with a depth first search:
+
+1. The state is the partial mapping (the "core") & the "terminal sets" of each graph:
+   unmapped nodes with an edge to (out) or from (in) a mapped node.
+2. Candidate pairs are taken from the out terminal sets (or in terminal sets, or if both are empty, any
+   unmapped nodes). Trying nodes next to the mapping first means dead ends are found quickly.
+3. A pair (n, m) is feasible if:
+   * Every edge between n & a mapped node has a matching edge between m & the mapped node's partner.
+   * (Look ahead) n has enough neighbours in the terminal sets & outside them to match m's neighbours.
+   * The optional node & edge predicates agree.
+4. Add a feasible pair, recurse, then remove it (backtrack) & try the next pair.
+5. When every node of the pattern (the smaller graph) is mapped, a match has been found.
+
+*/
+
+// Use the graph types from graph.rs
+#[path = "graph.rs"]
+mod graph;
+
+use graph::Graph;
+use std::collections::HashMap;
+
+// Marks a node that isn't in the mapping
+const NONE: usize = usize::MAX;
+
+// A graph with nodes numbered 0..n & edges stored as { neighbour: [weights] }
+// (several weights if there are parallel edges)
+struct IndexedGraph<'a> {
+    names: Vec<&'a String>,
+    successors: Vec<HashMap<usize, Vec<i32>>>,
+    predecessors: Vec<HashMap<usize, Vec<i32>>>,
+}
+
+impl<'a> IndexedGraph<'a> {
+    fn new<G: Graph>(graph: &'a G) -> IndexedGraph<'a> {
+        // Sort nodes so that matches are found in the same order on every run
+        let mut names: Vec<&String> = graph.nodes().into_iter().collect();
+        names.sort();
+        let index: HashMap<&String, usize> = names.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+
+        let mut successors = vec![HashMap::new(); names.len()];
+        let mut predecessors = vec![HashMap::new(); names.len()];
+        for (from_node, to_node, weight) in graph.edges() {
+            let (a, b) = (index[from_node], index[to_node]);
+            successors[a].entry(b).or_insert_with(Vec::new).push(weight);
+            predecessors[b].entry(a).or_insert_with(Vec::new).push(weight);
+        }
+        for weights in successors.iter_mut().chain(predecessors.iter_mut()).flat_map(|n| n.values_mut()) {
+            weights.sort_unstable();
+        }
+
+        IndexedGraph {
+            names,
+            successors,
+            predecessors,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.names.len()
+    }
+}
+
+#[derive(PartialEq)]
+enum Problem {
+    // Graph isomorphism: every count must be equal
+    Isomorphism,
+    // Induced subgraph isomorphism: the target (graph 1) may have more than the pattern (graph 2)
+    Subgraph,
+}
+
+// The state of the VF2 search
+// Graph 1 is the target & graph 2 is the pattern (the smaller graph)
+struct Matcher<'a, N, E> {
+    g1: IndexedGraph<'a>,
+    g2: IndexedGraph<'a>,
+    problem: Problem,
+    node_match: N,
+    edge_match: E,
+    // core_1[n] is the node of graph 2 that n is mapped to (or NONE), & the reverse for core_2
+    core_1: Vec<usize>,
+    core_2: Vec<usize>,
+    // The depth at which each node entered a terminal set (0 if it isn't in one)
+    in_1: Vec<usize>,
+    out_1: Vec<usize>,
+    in_2: Vec<usize>,
+    out_2: Vec<usize>,
+    depth: usize,
+    // Found mappings (pattern node -> target node) & when to stop looking
+    matches: Vec<HashMap<String, String>>,
+    limit: usize,
+}
+
+impl<'a, N, E> Matcher<'a, N, E>
+where
+    N: Fn(&str, &str) -> bool,
+    E: Fn(i32, i32) -> bool,
+{
+    fn new(target: IndexedGraph<'a>, pattern: IndexedGraph<'a>, problem: Problem, node_match: N, edge_match: E, limit: usize) -> Self {
+        let (n1, n2) = (target.len(), pattern.len());
+        Matcher {
+            g1: target,
+            g2: pattern,
+            problem,
+            node_match,
+            edge_match,
+            core_1: vec![NONE; n1],
+            core_2: vec![NONE; n2],
+            in_1: vec![0; n1],
+            out_1: vec![0; n1],
+            in_2: vec![0; n2],
+            out_2: vec![0; n2],
+            depth: 0,
+            matches: Vec::new(),
+            limit,
+        }
+    }
+
+    // Compare two counts: equal for isomorphism, at least as many in the target for subgraphs
+    fn compare(&self, count_1: usize, count_2: usize) -> bool {
+        match self.problem {
+            Problem::Isomorphism => count_1 == count_2,
+            Problem::Subgraph => count_1 >= count_2,
+        }
+    }
+
+    // Possible (graph 1 node, graph 2 node) pairs to extend the mapping with
+    fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let unmapped_1 = |terminal: &Vec<usize>| -> Vec<usize> {
+            (0..self.g1.len()).filter(|&n| terminal[n] != 0 && self.core_1[n] == NONE).collect()
+        };
+        let first_unmapped_2 = |terminal: &Vec<usize>| -> Option<usize> {
+            (0..self.g2.len()).find(|&m| terminal[m] != 0 && self.core_2[m] == NONE)
+        };
+
+        let (t1_out, t2_out) = (unmapped_1(&self.out_1), first_unmapped_2(&self.out_2));
+        if let (false, Some(m)) = (t1_out.is_empty(), t2_out) {
+            return t1_out.into_iter().map(|n| (n, m)).collect();
+        }
+        let (t1_in, t2_in) = (unmapped_1(&self.in_1), first_unmapped_2(&self.in_2));
+        if let (false, Some(m)) = (t1_in.is_empty(), t2_in) {
+            return t1_in.into_iter().map(|n| (n, m)).collect();
+        }
+
+        // With a connected pattern this only happens for the first pair
+        if t2_out.is_none() && t2_in.is_none() {
+            if let Some(m) = (0..self.g2.len()).find(|&m| self.core_2[m] == NONE) {
+                return (0..self.g1.len())
+                    .filter(|&n| self.core_1[n] == NONE)
+                    .map(|n| (n, m))
+                    .collect();
+            }
+        }
+        Vec::new()
+    }
+
+    // Do the edges between n and the mapped nodes of graph 1 match the edges between m and the
+    // mapped nodes of graph 2? Checked both ways round so that no edge is missed
+    fn edges_match(&self, n: usize, m: usize) -> bool {
+        let pairs = [
+            (&self.g1.successors[n], &self.g2.successors[m]),
+            (&self.g1.predecessors[n], &self.g2.predecessors[m]),
+        ];
+        for (edges_1, edges_2) in pairs.iter() {
+            for (neighbour, weights_1) in edges_1.iter() {
+                // A self-loop maps to a self-loop
+                let partner = if *neighbour == n { m } else { self.core_1[*neighbour] };
+                if partner == NONE {
+                    continue;
+                }
+                match edges_2.get(&partner) {
+                    None => return false,
+                    Some(weights_2) => {
+                        if weights_1.len() != weights_2.len()
+                            || !weights_1.iter().zip(weights_2).all(|(a, b)| (self.edge_match)(*a, *b))
+                        {
+                            return false;
+                        }
+                    }
+                }
+            }
+            for neighbour in edges_2.keys() {
+                let partner = if *neighbour == m { n } else { self.core_2[*neighbour] };
+                if partner != NONE && !edges_1.contains_key(&partner) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // Count the neighbours (in edges: &HashMap) that are unmapped & in / not in the terminal sets
+    fn look_ahead_counts(edges: &HashMap<usize, Vec<i32>>, core: &[usize], in_set: &[usize], out_set: &[usize]) -> [usize; 3] {
+        let mut counts = [0; 3];
+        for neighbour in edges.keys() {
+            if core[*neighbour] != NONE {
+                continue;
+            }
+            if in_set[*neighbour] != 0 {
+                counts[0] += 1;
+            }
+            if out_set[*neighbour] != 0 {
+                counts[1] += 1;
+            }
+            if in_set[*neighbour] == 0 && out_set[*neighbour] == 0 {
+                counts[2] += 1;
+            }
+        }
+        counts
+    }
+
+    fn is_feasible(&self, n: usize, m: usize) -> bool {
+        if !(self.node_match)(self.g1.names[n], self.g2.names[m]) {
+            return false;
+        }
+        if !self.edges_match(n, m) {
+            return false;
+        }
+        let sides = [
+            (&self.g1.predecessors[n], &self.g2.predecessors[m]),
+            (&self.g1.successors[n], &self.g2.successors[m]),
+        ];
+        for (edges_1, edges_2) in sides.iter() {
+            let counts_1 = Self::look_ahead_counts(edges_1, &self.core_1, &self.in_1, &self.out_1);
+            let counts_2 = Self::look_ahead_counts(edges_2, &self.core_2, &self.in_2, &self.out_2);
+            if !counts_1.iter().zip(counts_2.iter()).all(|(c1, c2)| self.compare(*c1, *c2)) {
+                return false;
+            }
+        }
+        true
+    }
+
+    // Add (n, m) to the mapping & grow the terminal sets with their neighbours
+    fn push(&mut self, n: usize, m: usize) {
+        self.depth += 1;
+        let depth = self.depth;
+        self.core_1[n] = m;
+        self.core_2[m] = n;
+
+        let mark = |terminal: &mut Vec<usize>, node: usize| {
+            if terminal[node] == 0 {
+                terminal[node] = depth;
+            }
+        };
+        mark(&mut self.in_1, n);
+        mark(&mut self.out_1, n);
+        mark(&mut self.in_2, m);
+        mark(&mut self.out_2, m);
+        for p in self.g1.predecessors[n].keys() {
+            mark(&mut self.in_1, *p);
+        }
+        for s in self.g1.successors[n].keys() {
+            mark(&mut self.out_1, *s);
+        }
+        for p in self.g2.predecessors[m].keys() {
+            mark(&mut self.in_2, *p);
+        }
+        for s in self.g2.successors[m].keys() {
+            mark(&mut self.out_2, *s);
+        }
+    }
+
+    // Undo push(n, m)
+    fn pop(&mut self, n: usize, m: usize) {
+        let depth = self.depth;
+        self.core_1[n] = NONE;
+        self.core_2[m] = NONE;
+        for terminal in [&mut self.in_1, &mut self.out_1, &mut self.in_2, &mut self.out_2].iter_mut() {
+            for entry in terminal.iter_mut() {
+                if *entry == depth {
+                    *entry = 0;
+                }
+            }
+        }
+        self.depth -= 1;
+    }
+
+    fn search(&mut self) {
+        if self.matches.len() >= self.limit {
+            return;
+        }
+        if self.depth == self.g2.len() {
+            let mapping = (0..self.g2.len())
+                .map(|m| (self.g2.names[m].to_string(), self.g1.names[self.core_2[m]].to_string()))
+                .collect();
+            self.matches.push(mapping);
+            return;
+        }
+        for (n, m) in self.candidate_pairs() {
+            if self.is_feasible(n, m) {
+                self.push(n, m);
+                self.search();
+                self.pop(n, m);
+            }
+        }
+    }
+}
+
+// Mappings (graph 2 node -> graph 1 node) making graph 1 & graph 2 the same graph, up to limit
+fn isomorphisms<G1, G2, N, E>(g1: &G1, g2: &G2, node_match: N, edge_match: E, limit: usize) -> Vec<HashMap<String, String>>
+where
+    G1: Graph,
+    G2: Graph,
+    N: Fn(&str, &str) -> bool,
+    E: Fn(i32, i32) -> bool,
+{
+    // Quick checks before searching
+    if g1.is_directed() != g2.is_directed()
+        || g1.nodes().len() != g2.nodes().len()
+        || g1.edges().len() != g2.edges().len()
+    {
+        return Vec::new();
+    }
+    let mut matcher = Matcher::new(IndexedGraph::new(g1), IndexedGraph::new(g2), Problem::Isomorphism, node_match, edge_match, limit);
+    matcher.search();
+    matcher.matches
+}
+
+pub fn is_isomorphic<G1: Graph, G2: Graph>(g1: &G1, g2: &G2) -> bool {
+    is_isomorphic_matching(g1, g2, |_, _| true, |_, _| true)
+}
+
+// Isomorphism where mapped nodes (by name) & mapped edges (by weight) must also match
+pub fn is_isomorphic_matching<G1, G2, N, E>(g1: &G1, g2: &G2, node_match: N, edge_match: E) -> bool
+where
+    G1: Graph,
+    G2: Graph,
+    N: Fn(&str, &str) -> bool,
+    E: Fn(i32, i32) -> bool,
+{
+    !isomorphisms(g1, g2, node_match, edge_match, 1).is_empty()
+}
+
+// One mapping (g2 node -> g1 node) that makes the graphs the same, if there is one
+pub fn isomorphism<G1: Graph, G2: Graph>(g1: &G1, g2: &G2) -> Option<HashMap<String, String>> {
+    isomorphisms(g1, g2, |_, _| true, |_, _| true, 1).pop()
+}
+
+// Every mapping (pattern node -> target node) of the pattern onto an induced subgraph of the target
+pub fn subgraph_isomorphisms<G1: Graph, G2: Graph>(pattern: &G1, target: &G2) -> Vec<HashMap<String, String>> {
+    subgraph_isomorphisms_matching(pattern, target, |_, _| true, |_, _| true)
+}
+
+// Subgraph isomorphisms where node_match(pattern node, target node) & edge_match(pattern weight, target weight) hold
+pub fn subgraph_isomorphisms_matching<G1, G2, N, E>(pattern: &G1, target: &G2, node_match: N, edge_match: E) -> Vec<HashMap<String, String>>
+where
+    G1: Graph,
+    G2: Graph,
+    N: Fn(&str, &str) -> bool,
+    E: Fn(i32, i32) -> bool,
+{
+    if pattern.is_directed() != target.is_directed() || pattern.nodes().len() > target.nodes().len() {
+        return Vec::new();
+    }
+    // The matcher compares (target, pattern), so swap the predicate arguments back round
+    let mut matcher = Matcher::new(
+        IndexedGraph::new(target),
+        IndexedGraph::new(pattern),
+        Problem::Subgraph,
+        |t: &str, p: &str| node_match(p, t),
+        |t: i32, p: i32| edge_match(p, t),
+        usize::MAX,
+    );
+    matcher.search();
+    matcher.matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{DirectedGraph, UndirectedGraph};
+
+    fn mapping(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect()
+    }
+
+    #[test]
+    fn test_isomorphic_undirected() {
+        // A square a - b - c - d - a
+        let mut g1 = UndirectedGraph::new();
+        g1.add_edge(("a", "b", 1));
+        g1.add_edge(("b", "c", 1));
+        g1.add_edge(("c", "d", 1));
+        g1.add_edge(("d", "a", 1));
+
+        // The same square with nodes in a different order
+        let mut g2 = UndirectedGraph::new();
+        g2.add_edge(("w", "y", 1));
+        g2.add_edge(("y", "x", 1));
+        g2.add_edge(("x", "z", 1));
+        g2.add_edge(("z", "w", 1));
+
+        assert!(is_isomorphic(&g1, &g2));
+
+        // Every edge of g2 must map to an edge of g1
+        let map = isomorphism(&g1, &g2).unwrap();
+        for (from_node, to_node, _) in g2.edges() {
+            assert!(g1.neighbours(&map[from_node]).unwrap().iter().any(|(n, _)| *n == map[to_node]));
+        }
+
+        // A path of 4 nodes has a different shape
+        let mut g3 = UndirectedGraph::new();
+        g3.add_edge(("a", "b", 1));
+        g3.add_edge(("b", "c", 1));
+        g3.add_edge(("c", "d", 1));
+        g3.add_edge(("a", "c", 1));
+        assert!(!is_isomorphic(&g1, &g3));
+    }
+
+    #[test]
+    fn test_isomorphic_directed() {
+        let mut g1 = DirectedGraph::new();
+        g1.add_edge(("a", "b", 1));
+        g1.add_edge(("b", "c", 2));
+
+        let mut g2 = DirectedGraph::new();
+        g2.add_edge(("y", "z", 2));
+        g2.add_edge(("x", "y", 1));
+
+        assert!(is_isomorphic(&g1, &g2));
+        assert_eq!(isomorphism(&g1, &g2), Some(mapping(&[("x", "a"), ("y", "b"), ("z", "c")])));
+
+        // Reversing one edge changes the graph
+        let mut g3 = DirectedGraph::new();
+        g3.add_edge(("a", "b", 1));
+        g3.add_edge(("c", "b", 2));
+        assert!(!is_isomorphic(&g1, &g3));
+
+        // Directed & undirected graphs are never isomorphic
+        let mut g4 = UndirectedGraph::new();
+        g4.add_edge(("a", "b", 1));
+        assert!(!is_isomorphic(&g1, &g4));
+    }
+
+    #[test]
+    fn test_isomorphic_matching_weights() {
+        let mut g1 = DirectedGraph::new();
+        g1.add_edge(("a", "b", 1));
+        g1.add_edge(("b", "c", 2));
+
+        let mut g2 = DirectedGraph::new();
+        g2.add_edge(("x", "y", 2));
+        g2.add_edge(("y", "z", 1));
+
+        assert!(is_isomorphic(&g1, &g2));
+        assert!(!is_isomorphic_matching(&g1, &g2, |_, _| true, |w1, w2| w1 == w2));
+        assert!(is_isomorphic_matching(&g1, &g2, |_, _| true, |w1, w2| w1 + w2 == 3));
+    }
+
+    #[test]
+    fn test_subgraph_isomorphisms() {
+        let mut target = UndirectedGraph::new();
+        target.add_edge(("a", "b", 1));
+        target.add_edge(("b", "c", 1));
+        target.add_edge(("c", "a", 1));
+        target.add_edge(("c", "d", 1));
+
+        let mut triangle = UndirectedGraph::new();
+        triangle.add_edge(("x", "y", 1));
+        triangle.add_edge(("y", "z", 1));
+        triangle.add_edge(("z", "x", 1));
+
+        // Every ordering of a, b & c
+        let matches = subgraph_isomorphisms(&triangle, &target);
+        assert_eq!(matches.len(), 6);
+        assert!(matches.contains(&mapping(&[("x", "b"), ("y", "c"), ("z", "a")])));
+        assert!(matches.iter().all(|m| !m.values().any(|n| n == "d")));
+
+        // Induced subgraphs only: a path x - y - z doesn't match a, b, c (which also has the edge a - c)
+        let mut path = UndirectedGraph::new();
+        path.add_edge(("x", "y", 1));
+        path.add_edge(("y", "z", 1));
+        let matches = subgraph_isomorphisms(&path, &target);
+        // a - c - d & b - c - d, in both directions
+        assert_eq!(matches.len(), 4);
+        assert!(matches.iter().all(|m| m["y"] == "c"));
+    }
+
+    #[test]
+    fn test_subgraph_isomorphisms_matching() {
+        let mut target = DirectedGraph::new();
+        target.add_edge(("a", "b", 1));
+        target.add_edge(("b", "c", 5));
+        target.add_edge(("c", "d", 1));
+
+        let mut pattern = DirectedGraph::new();
+        pattern.add_edge(("x", "y", 1));
+
+        assert_eq!(subgraph_isomorphisms(&pattern, &target).len(), 3);
+
+        let matches = subgraph_isomorphisms_matching(&pattern, &target, |_, _| true, |p, t| p == t);
+        assert_eq!(matches, vec![mapping(&[("x", "a"), ("y", "b")]), mapping(&[("x", "c"), ("y", "d")])]);
+
+        let matches = subgraph_isomorphisms_matching(&pattern, &target, |p, t| p != "x" || t == "c", |_, _| true);
+        assert_eq!(matches, vec![mapping(&[("x", "c"), ("y", "d")])]);
+    }
+}
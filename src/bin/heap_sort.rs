@@ -36,27 +36,56 @@ Heap sort steps:
 
 */
 
+// Counting & tracing what the sort does (see src/sort_instrumentation.rs)
+use rust_dsa::sort_instrumentation::{Instrumentation, SortEvent};
+use std::cmp::Ordering;
+
 pub fn heap_sort<T: Ord>(arr: &mut [T]) {
+    heap_sort_by(arr, |a, b| a.cmp(b))
+}
+
+// The heap is then a max heap by compare (the root is the element that compare puts last)
+pub fn heap_sort_by<T, F: FnMut(&T, &T) -> Ordering>(arr: &mut [T], compare: F) {
+    heap_sort_observed(arr, compare, |_| ())
+}
+
+// The sort itself, telling observe about every step
+pub fn heap_sort_observed<T, F, O>(arr: &mut [T], mut compare: F, mut observe: O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: FnMut(SortEvent),
+{
     if arr.len() <= 1 {
         return;
     }
 
-    heapify(arr);
+    observe(SortEvent::Pass);
+    heapify(arr, &mut compare, &mut observe);
 
     for end in (1..arr.len()).rev() {
+        observe(SortEvent::Pass);
         arr.swap(0, end);
-        move_down(&mut arr[..end], 0)
+        observe(SortEvent::Swap(0, end));
+        move_down(&mut arr[..end], 0, &mut compare, &mut observe)
     }
 }
 
-fn heapify<T: Ord>(arr: &mut [T]) {
+fn heapify<T, F, O>(arr: &mut [T], compare: &mut F, observe: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: FnMut(SortEvent),
+{
     let last_parent = (arr.len() - 2) / 2;
     for i in (0..=last_parent).rev() {
-        move_down(arr, i);
+        move_down(arr, i, compare, observe);
     }
 }
 
-fn move_down<T: Ord>(arr: &mut [T], mut root: usize) {
+fn move_down<T, F, O>(arr: &mut [T], mut root: usize, compare: &mut F, observe: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: FnMut(SortEvent),
+{
 
     let last = arr.len() - 1;
 
@@ -71,14 +100,18 @@ fn move_down<T: Ord>(arr: &mut [T], mut root: usize) {
         // right_child(i) = 2*i + 2 = left_child(i) + 1
         let right = left + 1;
 
-        let max = if right <= last && arr[right] > arr[left] {
-            right
-        } else {
-            left
-        };
+        let mut max = left;
+        if right <= last {
+            observe(SortEvent::Compare(right, left));
+            if compare(&arr[right], &arr[left]) == Ordering::Greater {
+                max = right;
+            }
+        }
 
-        if arr[max] > arr[root] {
+        observe(SortEvent::Compare(max, root));
+        if compare(&arr[max], &arr[root]) == Ordering::Greater {
             arr.swap(root, max);
+            observe(SortEvent::Swap(root, max));
         }
 
         root = max
@@ -87,10 +120,40 @@ fn move_down<T: Ord>(arr: &mut [T], mut root: usize) {
 
 }
 
+// Sort, counting comparisons, swaps & passes
+// (building the heap is one pass & moving each root to the sorted part is another)
+pub fn heap_sort_instrumented<T: Ord>(arr: &mut [T], instrumentation: &mut Instrumentation) {
+    heap_sort_instrumented_by(arr, |a, b| a.cmp(b), instrumentation)
+}
+
+pub fn heap_sort_instrumented_by<T, F: FnMut(&T, &T) -> Ordering>(
+    arr: &mut [T],
+    compare: F,
+    instrumentation: &mut Instrumentation,
+) {
+    heap_sort_observed(arr, compare, |event| instrumentation.record(event))
+}
+
+pub fn heap_sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(arr: &mut [T], mut key: F) {
+    heap_sort_by(arr, |a, b| key(a).cmp(&key(b)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn instrumented_counts() {
+        let mut arr = vec![4, 3, 2, 1];
+        let mut instrumentation = Instrumentation::new();
+        heap_sort_instrumented(&mut arr, &mut instrumentation);
+        assert_eq!(arr, vec![1, 2, 3, 4]);
+        assert_eq!(
+            (instrumentation.comparisons, instrumentation.swaps, instrumentation.passes),
+            (7, 4, 4)
+        );
+    }
+
     #[test]
     fn empty() {
         let mut arr: Vec<i32> = Vec::new();
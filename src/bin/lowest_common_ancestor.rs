@@ -0,0 +1,405 @@
+/*
+
+How it works:
+
+Lowest Common Ancestor (Binary Lifting) & Rooted Tree Queries
+
+A tree is a connected graph with no cycles. Picking one node as the root gives every other node
+a parent (its neighbour on the way to the root), a depth (the number of edges to the root) and
+a subtree (itself & every node below it).
+
+The lowest common ancestor (LCA) of a and b is the deepest node that is an ancestor of both.
+Consider the tree rooted at a:
+
+        a
+       / \
+      b   c
+     / \
+    d   e
+
+lca(d, e) = b, lca(d, c) = a, lca(b, e) = b
+
+Binary lifting stores, for every node, its 1st, 2nd, 4th, 8th... ancestor:
+
+up[0][v] = parent(v)
+up[j][v] = up[j - 1][up[j - 1][v]]   (the 2^j-th ancestor is the 2^(j-1)-th ancestor of the 2^(j-1)-th ancestor)
+
+Any jump of k levels is then at most log(n) hops (one for each bit set in k).
+
+To find lca(a, b):
+
+1. Lift the deeper node until both nodes are at the same depth.
+2. If they are now the same node, that node is the LCA.
+3. Otherwise, lift both by the largest jumps that keep them apart (from largest to smallest).
+   They finish as children of the LCA.
+
+The distance between a and b is then depth(a) + depth(b) - 2 * depth(lca(a, b))
+(using the sum of edge weights on the way to the root as the "depth").
+
+*/
+
+// Use the Edge type from minimum_spanning_tree.rs (& the graph types from graph.rs, which it includes)
+#[path = "minimum_spanning_tree.rs"]
+mod minimum_spanning_tree;
+
+use minimum_spanning_tree::graph::{Graph, GraphError, UndirectedGraph};
+use minimum_spanning_tree::Edge;
+use std::collections::{HashMap, VecDeque};
+
+pub struct RootedTree {
+    // Nodes are stored by position, with the root at position 0
+    names: Vec<String>,
+    index: HashMap<String, usize>,
+    // up[j][v] is the 2^j-th ancestor of v (the root is its own ancestor)
+    up: Vec<Vec<usize>>,
+    // Number of edges between each node & the root
+    depth: Vec<usize>,
+    // Sum of the edge weights between each node & the root
+    weighted_depth: Vec<i64>,
+    subtree_size: Vec<usize>,
+}
+
+impl RootedTree {
+    // Build from a tree stored in an UndirectedGraph
+    // GraphError::NotATree if the graph has a cycle or is disconnected
+    pub fn from_graph(graph: &UndirectedGraph, root: &str) -> Result<RootedTree, GraphError> {
+        if !graph.contains(root) {
+            return Err(GraphError::NodeNotInGraph(root.to_string()));
+        }
+        let mut names: Vec<String> = graph.nodes().into_iter().cloned().collect();
+        names.sort();
+
+        let index: HashMap<&String, usize> = names.iter().enumerate().map(|(i, n)| (n, i)).collect();
+        let mut adjacency: Vec<Vec<(usize, i64)>> = vec![Vec::new(); names.len()];
+        for (from_node, to_node, weight) in graph.edges() {
+            adjacency[index[from_node]].push((index[to_node], weight as i64));
+        }
+        // Each undirected edge is stored twice (a -> b & b -> a), but a self-loop is
+        // only added once, so check the edge count on the adjacency lists
+        let edge_entries: usize = adjacency.iter().map(|a| a.len()).sum();
+        if edge_entries != 2 * (names.len() - 1) {
+            return Err(GraphError::NotATree);
+        }
+
+        let root = index[&root.to_string()];
+        RootedTree::build(names, adjacency, root)
+    }
+
+    // Build from a list of edges (e.g. the result of kruskal)
+    // Nodes are named by their number, i.e. node 3 is "3"
+    // GraphError::NotATree if root isn't an endpoint of an edge (unless there are no edges)
+    // or the edges have a cycle or are disconnected
+    pub fn from_edges(edges: &[Edge], root: i64) -> Result<RootedTree, GraphError> {
+        let mut ids: Vec<i64> = vec![root];
+        for edge in edges {
+            ids.push(edge.source);
+            ids.push(edge.destination);
+        }
+        ids.sort_unstable();
+        ids.dedup();
+        if edges.len() + 1 != ids.len() {
+            return Err(GraphError::NotATree);
+        }
+
+        let index: HashMap<i64, usize> = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let mut adjacency: Vec<Vec<(usize, i64)>> = vec![Vec::new(); ids.len()];
+        for edge in edges {
+            let (a, b) = (index[&edge.source], index[&edge.destination]);
+            adjacency[a].push((b, edge.cost));
+            adjacency[b].push((a, edge.cost));
+        }
+
+        let names = ids.iter().map(|id| id.to_string()).collect();
+        RootedTree::build(names, adjacency, index[&root])
+    }
+
+    // Breadth first search from the root to find each node's parent & depth, then fill the
+    // binary lifting table & subtree sizes
+    // GraphError::NotATree if some node can't be reached from the root
+    fn build(names: Vec<String>, adjacency: Vec<Vec<(usize, i64)>>, root: usize) -> Result<RootedTree, GraphError> {
+        let n = names.len();
+        let mut parent = vec![usize::MAX; n];
+        let mut depth = vec![0; n];
+        let mut weighted_depth = vec![0; n];
+
+        // BFS order, so every node comes after its parent
+        let mut order: Vec<usize> = Vec::with_capacity(n);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        parent[root] = root;
+        queue.push_back(root);
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for &(u, weight) in &adjacency[v] {
+                if parent[u] == usize::MAX {
+                    parent[u] = v;
+                    depth[u] = depth[v] + 1;
+                    weighted_depth[u] = weighted_depth[v] + weight;
+                    queue.push_back(u);
+                }
+            }
+        }
+        if order.len() != n {
+            return Err(GraphError::NotATree);
+        }
+
+        // Renumber nodes in BFS order so the root is at position 0
+        let mut position = vec![0; n];
+        for (i, &v) in order.iter().enumerate() {
+            position[v] = i;
+        }
+        let names: Vec<String> = order.iter().map(|&v| names[v].to_string()).collect();
+        let parent: Vec<usize> = order.iter().map(|&v| position[parent[v]]).collect();
+        let depth: Vec<usize> = order.iter().map(|&v| depth[v]).collect();
+        let weighted_depth: Vec<i64> = order.iter().map(|&v| weighted_depth[v]).collect();
+
+        // Binary lifting table with enough levels to jump the full depth of the tree
+        let max_depth = *depth.iter().max().unwrap();
+        let mut levels = 1;
+        while (1 << levels) <= max_depth {
+            levels += 1;
+        }
+        let mut up = vec![parent];
+        for j in 1..levels {
+            let previous = &up[j - 1];
+            let next: Vec<usize> = (0..n).map(|v| previous[previous[v]]).collect();
+            up.push(next);
+        }
+
+        // Children come after their parent in BFS order, so work backwards adding sizes up the tree
+        let mut subtree_size = vec![1; n];
+        for v in (1..n).rev() {
+            subtree_size[up[0][v]] += subtree_size[v];
+        }
+
+        let index = names.iter().enumerate().map(|(i, name)| (name.to_string(), i)).collect();
+
+        Ok(RootedTree {
+            names,
+            index,
+            up,
+            depth,
+            weighted_depth,
+            subtree_size,
+        })
+    }
+
+    fn position(&self, node: &str) -> Result<usize, GraphError> {
+        match self.index.get(node) {
+            None => Err(GraphError::NodeNotInGraph(node.to_string())),
+            Some(i) => Ok(*i),
+        }
+    }
+
+    // Jump k levels up from v (k must be <= depth of v)
+    fn lift(&self, mut v: usize, k: usize) -> usize {
+        for (j, level) in self.up.iter().enumerate() {
+            if k & (1 << j) != 0 {
+                v = level[v];
+            }
+        }
+        v
+    }
+
+    fn lca_position(&self, mut a: usize, mut b: usize) -> usize {
+        // 1. Lift the deeper node to the same depth
+        if self.depth[a] < self.depth[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        a = self.lift(a, self.depth[a] - self.depth[b]);
+
+        // 2. b was an ancestor of a
+        if a == b {
+            return a;
+        }
+
+        // 3. Take the largest jumps that don't reach a common ancestor
+        for level in self.up.iter().rev() {
+            if level[a] != level[b] {
+                a = level[a];
+                b = level[b];
+            }
+        }
+        self.up[0][a]
+    }
+
+    pub fn root(&self) -> &String {
+        &self.names[0]
+    }
+
+    // Number of nodes in the tree
+    pub fn size(&self) -> usize {
+        self.names.len()
+    }
+
+    // None for the root
+    pub fn parent(&self, node: &str) -> Result<Option<&String>, GraphError> {
+        let v = self.position(node)?;
+        if v == 0 {
+            return Ok(None);
+        }
+        Ok(Some(&self.names[self.up[0][v]]))
+    }
+
+    // Number of edges between node & the root
+    pub fn depth(&self, node: &str) -> Result<usize, GraphError> {
+        Ok(self.depth[self.position(node)?])
+    }
+
+    // Number of nodes in the subtree below node (including node)
+    pub fn subtree_size(&self, node: &str) -> Result<usize, GraphError> {
+        Ok(self.subtree_size[self.position(node)?])
+    }
+
+    pub fn lca(&self, a: &str, b: &str) -> Result<&String, GraphError> {
+        let (a, b) = (self.position(a)?, self.position(b)?);
+        Ok(&self.names[self.lca_position(a, b)])
+    }
+
+    // The ancestor k levels above node (the 0th ancestor is node itself)
+    // None if node is less than k levels deep
+    pub fn kth_ancestor(&self, node: &str, k: usize) -> Result<Option<&String>, GraphError> {
+        let v = self.position(node)?;
+        if k > self.depth[v] {
+            return Ok(None);
+        }
+        Ok(Some(&self.names[self.lift(v, k)]))
+    }
+
+    // Number of edges on the path between a & b
+    pub fn hop_distance(&self, a: &str, b: &str) -> Result<usize, GraphError> {
+        let (a, b) = (self.position(a)?, self.position(b)?);
+        let lca = self.lca_position(a, b);
+        Ok(self.depth[a] + self.depth[b] - 2 * self.depth[lca])
+    }
+
+    // Sum of the edge weights on the path between a & b
+    pub fn distance(&self, a: &str, b: &str) -> Result<i64, GraphError> {
+        let (a, b) = (self.position(a)?, self.position(b)?);
+        let lca = self.lca_position(a, b);
+        Ok(self.weighted_depth[a] + self.weighted_depth[b] - 2 * self.weighted_depth[lca])
+    }
+
+    // Nodes on the path from a to b (including a & b)
+    pub fn path(&self, a: &str, b: &str) -> Result<Vec<&String>, GraphError> {
+        let (a, b) = (self.position(a)?, self.position(b)?);
+        let lca = self.lca_position(a, b);
+
+        let mut path: Vec<&String> = Vec::new();
+        let mut v = a;
+        while v != lca {
+            path.push(&self.names[v]);
+            v = self.up[0][v];
+        }
+        path.push(&self.names[lca]);
+
+        // Climb from b to the lca, then add those nodes in reverse
+        let mut tail: Vec<&String> = Vec::new();
+        let mut v = b;
+        while v != lca {
+            tail.push(&self.names[v]);
+            v = self.up[0][v];
+        }
+        path.extend(tail.into_iter().rev());
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minimum_spanning_tree::kruskal;
+
+    fn example_tree() -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("a", "c", 2));
+        graph.add_edge(("b", "d", 3));
+        graph.add_edge(("b", "e", 4));
+        graph.add_edge(("e", "f", 5));
+        graph
+    }
+
+    #[test]
+    fn test_lca() {
+        let tree = RootedTree::from_graph(&example_tree(), "a").unwrap();
+
+        assert_eq!(tree.root(), "a");
+        assert_eq!(tree.size(), 6);
+        assert_eq!(tree.lca("d", "e").unwrap(), "b");
+        assert_eq!(tree.lca("d", "c").unwrap(), "a");
+        assert_eq!(tree.lca("b", "f").unwrap(), "b");
+        assert_eq!(tree.lca("f", "f").unwrap(), "f");
+        assert_eq!(tree.lca("a", "z"), Err(GraphError::NodeNotInGraph(String::from("z"))));
+    }
+
+    #[test]
+    fn test_tree_queries() {
+        let tree = RootedTree::from_graph(&example_tree(), "a").unwrap();
+
+        assert_eq!(tree.parent("a").unwrap(), None);
+        assert_eq!(tree.parent("f").unwrap().unwrap(), "e");
+        assert_eq!(tree.depth("f").unwrap(), 3);
+
+        assert_eq!(tree.kth_ancestor("f", 0).unwrap().unwrap(), "f");
+        assert_eq!(tree.kth_ancestor("f", 2).unwrap().unwrap(), "b");
+        assert_eq!(tree.kth_ancestor("f", 3).unwrap().unwrap(), "a");
+        assert_eq!(tree.kth_ancestor("f", 4).unwrap(), None);
+
+        assert_eq!(tree.subtree_size("a").unwrap(), 6);
+        assert_eq!(tree.subtree_size("b").unwrap(), 4);
+        assert_eq!(tree.subtree_size("c").unwrap(), 1);
+
+        assert_eq!(tree.hop_distance("f", "c").unwrap(), 4);
+        assert_eq!(tree.distance("f", "c").unwrap(), 5 + 4 + 1 + 2);
+        assert_eq!(tree.distance("d", "d").unwrap(), 0);
+        assert_eq!(tree.path("f", "c").unwrap(), vec!["f", "e", "b", "a", "c"]);
+        assert_eq!(tree.path("b", "f").unwrap(), vec!["b", "e", "f"]);
+    }
+
+    #[test]
+    fn test_not_a_tree() {
+        let mut graph = example_tree();
+        assert_eq!(
+            RootedTree::from_graph(&graph, "z").err(),
+            Some(GraphError::NodeNotInGraph(String::from("z")))
+        );
+
+        // A cycle
+        graph.add_edge(("c", "f", 1));
+        assert_eq!(RootedTree::from_graph(&graph, "a").err(), Some(GraphError::NotATree));
+
+        // Disconnected
+        let mut graph = example_tree();
+        graph.add_node("g");
+        assert_eq!(RootedTree::from_graph(&graph, "a").err(), Some(GraphError::NotATree));
+    }
+
+    #[test]
+    fn test_from_minimum_spanning_tree() {
+        let edges = vec![
+            Edge::new(0, 1, 7),
+            Edge::new(0, 3, 5),
+            Edge::new(1, 2, 8),
+            Edge::new(1, 3, 9),
+            Edge::new(1, 4, 7),
+            Edge::new(2, 4, 5),
+            Edge::new(3, 4, 15),
+            Edge::new(3, 5, 6),
+            Edge::new(4, 5, 8),
+            Edge::new(4, 6, 9),
+            Edge::new(5, 6, 11),
+        ];
+        let (_, mst) = kruskal(edges, 7);
+
+        let tree = RootedTree::from_edges(&mst, 0).unwrap();
+
+        // The tree is 0 - 3 - 5, 0 - 1 - 4 - 2, 4 - 6
+        assert_eq!(tree.lca("5", "6").unwrap(), "0");
+        assert_eq!(tree.lca("2", "6").unwrap(), "4");
+        assert_eq!(tree.distance("5", "6").unwrap(), 6 + 5 + 7 + 7 + 9);
+        assert_eq!(tree.subtree_size("1").unwrap(), 4);
+
+        // Missing an edge, so not a tree
+        assert_eq!(RootedTree::from_edges(&mst[1..], 0).err(), Some(GraphError::NotATree));
+    }
+}
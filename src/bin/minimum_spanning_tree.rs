@@ -23,15 +23,49 @@ Other Spanning trees have higher weight:
 
 (AB, BC, CD), weight = 6
 
+Graphs with named nodes (any undirected Graph from graph.rs) are handled by minimum_spanning_forest.
+If the graph is disconnected there is no spanning tree, so the result is a spanning forest:
+a minimum spanning tree for each connected component.
+
+Three algorithms find the same (minimum) total cost:
+
+Kruskal: take edges cheapest first, skipping any that would make a cycle. Sorting the edges
+         dominates, so it suits sparse graphs.
+Prim: grow one tree from a start vertex, always adding the cheapest edge leaving the tree
+      (kept in a binary heap). Suits dense graphs.
+Boruvka: every tree picks the cheapest edge leaving it & all of them are added at once, at least
+         halving the number of trees each round. Each tree's choice is independent, so a round can be
+         split between threads.
+
+With equal edge costs there can be more than one minimum spanning tree, so the edges chosen may differ.
+
 */
-use std::vec::Vec;s
+
+// Use the graph types from graph.rs (public so files using this one share the same graph types)
+#[path = "graph.rs"]
+pub mod graph;
+
+// Repeatable random inputs for the tests
+#[cfg(test)]
+#[path = "test_random.rs"]
+mod test_random;
+
+use graph::{Graph, GraphError, UndirectedGraph};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::hash::Hash;
+use std::vec::Vec;
 
 // Define the structure of an edge (an object with source, destination, and weight)
-#[derive(Debug)]
+// With the `serde` feature an Edge is written as {"source": 0, "destination": 1, "cost": 7}
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edge {
-    source: i64,
-    destination: i64,
-    cost: i64,
+    pub source: i64,
+    pub destination: i64,
+    pub cost: i64,
 }
 
 impl PartialEq for Edge {
@@ -49,7 +83,7 @@ impl Eq for Edge {}
 // Because this in an implementation of the Edge type
 // Self will return an Edge
 impl Edge {
-    fn new(source: i64, destination: i64, cost: i64) -> Self {
+    pub fn new(source: i64, destination: i64, cost: i64) -> Self {
         Self {
             source,
             destination,
@@ -58,51 +92,641 @@ impl Edge {
     }
 }
 
-// Create a vector filled with integers from 0 to number_of_vertices
-fn make_sets(number_of_vertices: i64) -> Vec<i64> {
-    let mut parent: Vec<i64> = Vec::with_capacity(number_of_vertices as usize);
-    for i in 0..number_of_vertices {
-        parent.push(i);
+// Check that number_of_vertices isn't negative & every (position, vertex) is in 0..number_of_vertices
+// Returns the first vertex out of range
+fn check_vertices<I>(number_of_vertices: i64, vertices: I) -> Result<(), GraphError>
+where
+    I: IntoIterator<Item = (usize, i64)>,
+{
+    if number_of_vertices < 0 {
+        return Err(GraphError::NegativeVertexCount(number_of_vertices));
+    }
+    for (position, vertex) in vertices {
+        if !(0..number_of_vertices).contains(&vertex) {
+            return Err(GraphError::ItemVertexOutOfRange {
+                position,
+                vertex,
+                number_of_vertices,
+            });
+        }
+    }
+    Ok(())
+}
+
+// check_vertices for both ends of every edge (the position is the edge's position in edges)
+fn check_edges(edges: &[Edge], number_of_vertices: i64) -> Result<(), GraphError> {
+    check_vertices(
+        number_of_vertices,
+        edges.iter().enumerate().flat_map(|(i, edge)| [(i, edge.source), (i, edge.destination)]),
+    )
+}
+
+/*
+
+Disjoint Set (Union-Find)
+
+Keeps track of which elements are in the same set, where sets can only be merged (never split).
+Kruskal's algorithm uses it to check if an edge would join two nodes that are already connected.
+
+Each set is a tree stored as a parent for each element. The root of the tree names (represents) the set.
+
+* find: follow parents up to the root. Afterwards every element on the way is pointed straight at the root
+  (path compression), so later finds are faster.
+* union: point the root of the smaller set at the root of the larger set (union by size),
+  which keeps the trees shallow.
+
+Together these make each operation take (almost) constant time on average.
+
+*/
+pub struct DisjointSet {
+    // parent[x] is the element above x in its tree (roots are their own parent)
+    parent: Vec<usize>,
+    // size[root] is the number of elements in the set (only kept up to date for roots)
+    size: Vec<usize>,
+    // Number of separate sets
+    count: usize,
+}
+
+impl DisjointSet {
+    // Elements 0..number_of_elements, each in a set on its own
+    pub fn new(number_of_elements: usize) -> Self {
+        Self {
+            parent: (0..number_of_elements).collect(),
+            size: vec![1; number_of_elements],
+            count: number_of_elements,
+        }
+    }
+
+    // Add a new element in a set on its own & return it
+    pub fn add(&mut self) -> usize {
+        let x = self.parent.len();
+        self.parent.push(x);
+        self.size.push(1);
+        self.count += 1;
+        x
+    }
+
+    // Number of elements (in all sets)
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    // Number of separate sets
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Root of the set containing x, without changing the trees
+    fn root(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    // Representative (root) of the set containing x
+    // Panics if x is not an element (like indexing a Vec out of range)
+    pub fn find(&mut self, x: usize) -> usize {
+        let root = self.root(x);
+        // Path compression: point every element on the way at the root
+        let mut current = x;
+        while current != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    // Merge the sets containing x & y
+    // Returns false if they were already in the same set
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let mut root_x = self.find(x);
+        let mut root_y = self.find(y);
+        if root_x == root_y {
+            return false;
+        }
+        // Union by size: the larger set's root becomes the root of the merged set
+        if self.size[root_x] < self.size[root_y] {
+            std::mem::swap(&mut root_x, &mut root_y);
+        }
+        self.parent[root_y] = root_x;
+        self.size[root_x] += self.size[root_y];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same_set(&mut self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    // Number of elements in the set containing x
+    pub fn set_size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    // Elements in the same set as x (including x), in increasing order
+    pub fn members(&self, x: usize) -> impl Iterator<Item = usize> + '_ {
+        let root = self.root(x);
+        (0..self.len()).filter(move |y| self.root(*y) == root)
+    }
+
+    // Every set, each in increasing order & sorted by their smallest element
+    pub fn sets(&self) -> Vec<Vec<usize>> {
+        let mut position = vec![usize::MAX; self.len()];
+        let mut sets: Vec<Vec<usize>> = Vec::with_capacity(self.count);
+        for x in 0..self.len() {
+            let root = self.root(x);
+            if position[root] == usize::MAX {
+                position[root] = sets.len();
+                sets.push(Vec::new());
+            }
+            sets[position[root]].push(x);
+        }
+        sets
+    }
+}
+
+/*
+
+Weighted Disjoint Set (potential differences)
+
+Each element x has an unknown value v(x). Merging x & y records a constraint v(x) - v(y) = difference,
+after which the difference between any two elements in the same set is known.
+
+Alongside its parent, each element stores its offset: v(x) - v(parent). Adding up offsets on the way
+to the root gives v(x) - v(root), & for two elements in the same set:
+
+v(x) - v(y) = (v(x) - v(root)) - (v(y) - v(root))
+
+A constraint between two elements already in the same set is either implied (it matches the known difference)
+or a contradiction.
+
+*/
+pub struct WeightedDisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    // offset[x] = v(x) - v(parent[x])
+    offset: Vec<i64>,
+    count: usize,
+}
+
+// A constraint v(x) - v(y) = given, when v(x) - v(y) is already known to be something else
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contradiction {
+    pub x: usize,
+    pub y: usize,
+    pub known: i64,
+    pub given: i64,
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "v({}) - v({}) is already known to be {}, not {}",
+            self.x, self.y, self.known, self.given
+        )
+    }
+}
+
+impl Error for Contradiction {}
+
+impl WeightedDisjointSet {
+    pub fn new(number_of_elements: usize) -> Self {
+        Self {
+            parent: (0..number_of_elements).collect(),
+            size: vec![1; number_of_elements],
+            offset: vec![0; number_of_elements],
+            count: number_of_elements,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Root of the set containing x & v(x) - v(root)
+    pub fn find(&mut self, x: usize) -> (usize, i64) {
+        let mut path = Vec::new();
+        let mut root = x;
+        while self.parent[root] != root {
+            path.push(root);
+            root = self.parent[root];
+        }
+        // Path compression, starting nearest the root so each parent's offset is already relative to the root
+        for node in path.into_iter().rev() {
+            self.offset[node] += self.offset[self.parent[node]];
+            self.parent[node] = root;
+        }
+        (root, self.offset[x])
+    }
+
+    pub fn same_set(&mut self, x: usize, y: usize) -> bool {
+        self.find(x).0 == self.find(y).0
+    }
+
+    // v(x) - v(y), or None if x & y are in different sets (so it isn't known)
+    pub fn difference(&mut self, x: usize, y: usize) -> Option<i64> {
+        let (root_x, offset_x) = self.find(x);
+        let (root_y, offset_y) = self.find(y);
+        if root_x == root_y {
+            Some(offset_x - offset_y)
+        } else {
+            None
+        }
+    }
+
+    // Record v(x) - v(y) = difference, merging their sets
+    // Returns Ok(false) if the constraint was already implied & a Contradiction if it can't hold
+    pub fn union(&mut self, x: usize, y: usize, difference: i64) -> Result<bool, Contradiction> {
+        let (root_x, offset_x) = self.find(x);
+        let (root_y, offset_y) = self.find(y);
+        if root_x == root_y {
+            let known = offset_x - offset_y;
+            if known != difference {
+                return Err(Contradiction {
+                    x,
+                    y,
+                    known,
+                    given: difference,
+                });
+            }
+            return Ok(false);
+        }
+
+        // v(root_y) - v(root_x) = (v(y) - offset_y) - (v(x) - offset_x), where v(x) - v(y) = difference
+        let root_y_minus_root_x = offset_x - offset_y - difference;
+        // Union by size, as in DisjointSet
+        if self.size[root_x] < self.size[root_y] {
+            self.parent[root_x] = root_y;
+            self.offset[root_x] = -root_y_minus_root_x;
+            self.size[root_y] += self.size[root_x];
+        } else {
+            self.parent[root_y] = root_x;
+            self.offset[root_y] = root_y_minus_root_x;
+            self.size[root_x] += self.size[root_y];
+        }
+        self.count -= 1;
+        Ok(true)
+    }
+}
+
+// A disjoint set over any hashable labels (e.g. node names) instead of 0..n
+// Each label is given the next index in an inner DisjointSet
+pub struct KeyedDisjointSet<T> {
+    index: HashMap<T, usize>,
+    labels: Vec<T>,
+    sets: DisjointSet,
+}
+
+impl<T: Hash + Eq + Clone> KeyedDisjointSet<T> {
+    pub fn new() -> Self {
+        Self {
+            index: HashMap::new(),
+            labels: Vec::new(),
+            sets: DisjointSet::new(0),
+        }
+    }
+
+    // Add label in a set on its own
+    // Returns false (& changes nothing) if it was already added
+    pub fn add(&mut self, label: T) -> bool {
+        if self.index.contains_key(&label) {
+            return false;
+        }
+        let x = self.sets.add();
+        self.index.insert(label.clone(), x);
+        self.labels.push(label);
+        true
+    }
+
+    pub fn contains(&self, label: &T) -> bool {
+        self.index.contains_key(label)
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.sets.count()
+    }
+
+    // Representative label of the set containing label, or None if it was never added
+    pub fn find(&mut self, label: &T) -> Option<&T> {
+        let x = *self.index.get(label)?;
+        let root = self.sets.find(x);
+        Some(&self.labels[root])
+    }
+
+    // Merge the sets containing a & b, adding either label if it is new
+    // Returns false if they were already in the same set
+    pub fn union(&mut self, a: T, b: T) -> bool {
+        self.add(a.clone());
+        self.add(b.clone());
+        self.sets.union(self.index[&a], self.index[&b])
+    }
+
+    // False if either label was never added
+    pub fn same_set(&mut self, a: &T, b: &T) -> bool {
+        match (self.index.get(a), self.index.get(b)) {
+            (Some(x), Some(y)) => self.sets.same_set(*x, *y),
+            _ => false,
+        }
+    }
+
+    pub fn set_size(&mut self, label: &T) -> Option<usize> {
+        let x = *self.index.get(label)?;
+        Some(self.sets.set_size(x))
+    }
+
+    // Labels in the same set as label (in the order they were added), or None if it was never added
+    pub fn members(&self, label: &T) -> Option<impl Iterator<Item = &T> + '_> {
+        let x = *self.index.get(label)?;
+        Some(self.sets.members(x).map(move |y| &self.labels[y]))
+    }
+
+    // Every set of labels, each in the order they were added
+    pub fn sets(&self) -> Vec<Vec<&T>> {
+        self.sets
+            .sets()
+            .into_iter()
+            .map(|set| set.into_iter().map(|x| &self.labels[x]).collect())
+            .collect()
+    }
+}
+
+impl<T: Hash + Eq + Clone> Default for KeyedDisjointSet<T> {
+    fn default() -> Self {
+        Self::new()
     }
-    parent
 }
 
-// Recursively find parent by index
-fn find(parent: &mut Vec<i64>, x: i64) -> i64 {
-    let idx: usize = x as usize;
-    if parent[idx] != x {
-        parent[idx] = find(parent, parent[idx]);
+/*
+
+Disjoint Set with Rollback
+
+Like DisjointSet, but unions can be undone (most recent first).
+
+Path compression changes many parents in one find, which would all need undoing, so it isn't used here.
+Union by rank alone keeps trees O(log n) deep, so find is still fast. Each union changes at most
+one parent & one rank, which are recorded on a stack (the history) so that they can be put back.
+
+*/
+pub struct RollbackDisjointSet {
+    parent: Vec<usize>,
+    // rank[root] is an upper bound on the height of the root's tree
+    rank: Vec<usize>,
+    count: usize,
+    // One entry per call to union: the (child root, rank of the parent root increased?) it changed, if anything
+    history: Vec<Option<(usize, bool)>>,
+}
+
+impl RollbackDisjointSet {
+    pub fn new(number_of_elements: usize) -> Self {
+        Self {
+            parent: (0..number_of_elements).collect(),
+            rank: vec![0; number_of_elements],
+            count: number_of_elements,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // Representative (root) of the set containing x (no path compression, so self isn't changed)
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn same_set(&self, x: usize, y: usize) -> bool {
+        self.find(x) == self.find(y)
+    }
+
+    // Merge the sets containing x & y, returning false if they were already in the same set
+    // Either way the call can be undone with rollback
+    pub fn union(&mut self, x: usize, y: usize) -> bool {
+        let mut root_x = self.find(x);
+        let mut root_y = self.find(y);
+        if root_x == root_y {
+            self.history.push(None);
+            return false;
+        }
+        // Union by rank: the shorter tree goes under the taller one
+        if self.rank[root_x] < self.rank[root_y] {
+            std::mem::swap(&mut root_x, &mut root_y);
+        }
+        self.parent[root_y] = root_x;
+        let rank_increased = self.rank[root_x] == self.rank[root_y];
+        if rank_increased {
+            self.rank[root_x] += 1;
+        }
+        self.count -= 1;
+        self.history.push(Some((root_y, rank_increased)));
+        true
+    }
+
+    // Position in the history, for rollback_to
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    // Undo the most recent union, returning false if there is nothing to undo
+    pub fn rollback(&mut self) -> bool {
+        match self.history.pop() {
+            None => false,
+            Some(None) => true,
+            Some(Some((child, rank_increased))) => {
+                let root = self.parent[child];
+                if rank_increased {
+                    self.rank[root] -= 1;
+                }
+                self.parent[child] = child;
+                self.count += 1;
+                true
+            }
+        }
+    }
+
+    // Undo every union since the snapshot was taken
+    pub fn rollback_to(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.rollback();
+        }
     }
-    parent[idx]
 }
 
-fn merge(parent: &mut Vec<i64>, x: i64, y: i64) {
-    let idx_x: usize = find(parent, x) as usize;
-    let parent_y: i64 = find(parent, y);
-    parent[idx_x] = parent_y;
+/*
+
+Offline Dynamic Connectivity
+
+Answers "are u & v connected?" at points in a log of edge insertions & deletions, knowing the whole log in advance.
+
+1. Each edge is present for an interval of the log (from its insertion to its deletion, or the end).
+2. Build a segment tree over the positions in the log. Each interval is split across O(log n) tree nodes
+   that exactly cover it.
+3. Walk the segment tree depth first with a RollbackDisjointSet: on entering a node, union its edges;
+   at a leaf (one position in the log) answer the query there; on leaving a node, roll back its unions.
+
+At each leaf the disjoint set holds exactly the edges present at that point, so the whole log takes
+O(n log^2 n) (log n nodes per edge & log n per find).
+
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectivityEvent {
+    Insert(i64, i64),
+    // Removes one copy of the edge (in either direction). Deleting an edge that isn't present does nothing
+    Delete(i64, i64),
+    Query(i64, i64),
 }
 
-fn is_same_set(parent: &mut Vec<i64>, x: i64, y: i64) -> bool {
-    find(parent, x) == find(parent, y)
+// Answer every Query in events (in order) for vertices 0..number_of_vertices
+// Returns an error for the first event with a vertex out of range
+pub fn offline_connectivity(events: &[ConnectivityEvent], number_of_vertices: i64) -> Result<Vec<bool>, GraphError> {
+    let vertices = events.iter().enumerate().flat_map(|(position, event)| {
+        let (u, v) = match event {
+            ConnectivityEvent::Insert(u, v) | ConnectivityEvent::Delete(u, v) | ConnectivityEvent::Query(u, v) => {
+                (*u, *v)
+            }
+        };
+        [(position, u), (position, v)]
+    });
+    check_vertices(number_of_vertices, vertices)?;
+    let n = events.len();
+
+    // 1. Interval [start, end) of the log that each edge is present for
+    let mut open: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    let mut intervals: Vec<(usize, usize, (usize, usize))> = Vec::new();
+    for (time, event) in events.iter().enumerate() {
+        match event {
+            ConnectivityEvent::Insert(u, v) => {
+                let key = ((*u).min(*v) as usize, (*u).max(*v) as usize);
+                open.entry(key).or_default().push(time);
+            }
+            ConnectivityEvent::Delete(u, v) => {
+                let key = ((*u).min(*v) as usize, (*u).max(*v) as usize);
+                if let Some(start) = open.get_mut(&key).and_then(|starts| starts.pop()) {
+                    intervals.push((start, time, key));
+                }
+            }
+            ConnectivityEvent::Query(_, _) => (),
+        }
+    }
+    for (key, starts) in open {
+        intervals.extend(starts.into_iter().map(|start| (start, n, key)));
+    }
+
+    // 2. Segment tree: node 1 covers [0, n) & node i has children 2i & 2i + 1 covering each half
+    let mut segments: Vec<Vec<(usize, usize)>> = vec![Vec::new(); 4 * n.max(1)];
+    for (start, end, edge) in intervals {
+        add_interval(&mut segments, 1, 0, n, start, end, edge);
+    }
+
+    // 3. Walk the tree
+    let mut answers = vec![None; n];
+    let mut sets = RollbackDisjointSet::new(number_of_vertices as usize);
+    if n > 0 {
+        answer_queries(&segments, 1, 0, n, events, &mut sets, &mut answers);
+    }
+    Ok(answers.into_iter().flatten().collect())
 }
 
+// Store edge in the nodes (under node, which covers [low, high)) that exactly cover [start, end)
+fn add_interval(
+    segments: &mut [Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    start: usize,
+    end: usize,
+    edge: (usize, usize),
+) {
+    if end <= low || high <= start {
+        return;
+    }
+    if start <= low && high <= end {
+        segments[node].push(edge);
+        return;
+    }
+    let middle = (low + high) / 2;
+    add_interval(segments, 2 * node, low, middle, start, end, edge);
+    add_interval(segments, 2 * node + 1, middle, high, start, end, edge);
+}
+
+fn answer_queries(
+    segments: &[Vec<(usize, usize)>],
+    node: usize,
+    low: usize,
+    high: usize,
+    events: &[ConnectivityEvent],
+    sets: &mut RollbackDisjointSet,
+    answers: &mut [Option<bool>],
+) {
+    let snapshot = sets.snapshot();
+    for (u, v) in segments[node].iter() {
+        sets.union(*u, *v);
+    }
+    if high - low == 1 {
+        if let ConnectivityEvent::Query(u, v) = events[low] {
+            answers[low] = Some(sets.same_set(u as usize, v as usize));
+        }
+    } else {
+        let middle = (low + high) / 2;
+        answer_queries(segments, 2 * node, low, middle, events, sets, answers);
+        answer_queries(segments, 2 * node + 1, middle, high, events, sets, answers);
+    }
+    sets.rollback_to(snapshot);
+}
+
+// Vertices must be in 0..number_of_vertices (anything else panics, see try_kruskal)
 pub fn kruskal(mut edges: Vec<Edge>, number_of_vertices: i64) -> (i64, Vec<Edge>) {
-    let mut parent: Vec<i64> = make_sets(number_of_vertices);
+    let mut sets = DisjointSet::new(number_of_vertices as usize);
 
-    edges.sort_unstable_by(|a, b| a.cost.cmp(&b.cost));
+    // A stable sort, so equal cost edges are taken in list order (second_best_mst & mst_sensitivity rely on this)
+    edges.sort_by_key(|edge| edge.cost);
     let mut total_cost: i64 = 0;
     let mut final_edges: Vec<Edge> = Vec::new();
-    let mut merge_count: i64 = 0;
     for edge in edges.iter() {
-        if merge_count >= number_of_vertices - 1 {
+        // A spanning tree is complete once every vertex is in one set
+        if sets.count() <= 1 {
             break;
         }
 
         let source: i64 = edge.source;
         let destination: i64 = edge.destination;
-        if !is_same_set(&mut parent, source, destination) {
-            merge(&mut parent, source, destination);
-            merge_count += 1;
+        // union returns false if source & destination are already connected (the edge would make a cycle)
+        if sets.union(source as usize, destination as usize) {
             let cost: i64 = edge.cost;
             total_cost += cost;
             let final_edge: Edge = Edge::new(source, destination, cost);
@@ -112,9 +736,544 @@ pub fn kruskal(mut edges: Vec<Edge>, number_of_vertices: i64) -> (i64, Vec<Edge>
     (total_cost, final_edges)
 }
 
+// kruskal, after checking that every edge's vertices are in 0..number_of_vertices
+// Returns an error for the first invalid edge found instead of panicking
+pub fn try_kruskal(edges: Vec<Edge>, number_of_vertices: i64) -> Result<(i64, Vec<Edge>), GraphError> {
+    check_edges(&edges, number_of_vertices)?;
+    Ok(kruskal(edges, number_of_vertices))
+}
+
+// Prim's algorithm, with the same result as kruskal
+// Disconnected graphs give a spanning forest (a new tree is started from each vertex not yet reached)
+// Vertices must be in 0..number_of_vertices (anything else panics, see try_prim)
+pub fn prim(edges: Vec<Edge>, number_of_vertices: i64) -> (i64, Vec<Edge>) {
+    let n = number_of_vertices as usize;
+
+    // Positions (in edges) of the edges touching each vertex
+    let mut touching: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, edge) in edges.iter().enumerate() {
+        touching[edge.source as usize].push(i);
+        touching[edge.destination as usize].push(i);
+    }
+
+    let mut in_tree = vec![false; n];
+    let mut total_cost: i64 = 0;
+    let mut final_edges: Vec<Edge> = Vec::new();
+    // Edges leaving the tree as Reverse((cost, position)) so the cheapest is popped first
+    let mut heap = BinaryHeap::new();
+
+    for start in 0..n {
+        if in_tree[start] {
+            continue;
+        }
+        in_tree[start] = true;
+        heap.extend(touching[start].iter().map(|i| Reverse((edges[*i].cost, *i))));
+
+        while let Some(Reverse((cost, i))) = heap.pop() {
+            let edge = &edges[i];
+            // Both ends may have joined the tree since the edge was pushed
+            let next = match (in_tree[edge.source as usize], in_tree[edge.destination as usize]) {
+                (true, false) => edge.destination as usize,
+                (false, true) => edge.source as usize,
+                _ => continue,
+            };
+            in_tree[next] = true;
+            total_cost += cost;
+            final_edges.push(Edge::new(edge.source, edge.destination, cost));
+            heap.extend(touching[next].iter().map(|i| Reverse((edges[*i].cost, *i))));
+        }
+    }
+    (total_cost, final_edges)
+}
+
+// prim, returning an error for the first invalid edge (as try_kruskal does)
+pub fn try_prim(edges: Vec<Edge>, number_of_vertices: i64) -> Result<(i64, Vec<Edge>), GraphError> {
+    check_edges(&edges, number_of_vertices)?;
+    Ok(prim(edges, number_of_vertices))
+}
+
+// Boruvka's algorithm, with the same result as kruskal
+// Vertices must be in 0..number_of_vertices (anything else panics, see try_boruvka)
+pub fn boruvka(edges: Vec<Edge>, number_of_vertices: i64) -> (i64, Vec<Edge>) {
+    let n = number_of_vertices as usize;
+    let mut sets = DisjointSet::new(n);
+    let mut total_cost: i64 = 0;
+    let mut final_edges: Vec<Edge> = Vec::new();
+
+    loop {
+        // Cheapest edge leaving each tree (by the tree's root), as a position in edges
+        // Ties are broken by position so that two trees can't pick different equal edges & make a cycle
+        let mut cheapest: Vec<Option<usize>> = vec![None; n];
+        for (i, edge) in edges.iter().enumerate() {
+            let root_source = sets.find(edge.source as usize);
+            let root_destination = sets.find(edge.destination as usize);
+            if root_source == root_destination {
+                continue;
+            }
+            for root in [root_source, root_destination] {
+                let better = cheapest[root].is_none_or(|j| (edge.cost, i) < (edges[j].cost, j));
+                if better {
+                    cheapest[root] = Some(i);
+                }
+            }
+        }
+
+        // Add every chosen edge (two trees may have chosen the same one)
+        let mut added = false;
+        for i in cheapest.into_iter().flatten() {
+            let edge = &edges[i];
+            if sets.union(edge.source as usize, edge.destination as usize) {
+                total_cost += edge.cost;
+                final_edges.push(Edge::new(edge.source, edge.destination, edge.cost));
+                added = true;
+            }
+        }
+        // No tree has an edge leaving it, so every tree is a whole connected component
+        if !added {
+            break;
+        }
+    }
+    (total_cost, final_edges)
+}
+
+// boruvka, returning an error for the first invalid edge (as try_kruskal does)
+pub fn try_boruvka(edges: Vec<Edge>, number_of_vertices: i64) -> Result<(i64, Vec<Edge>), GraphError> {
+    check_edges(&edges, number_of_vertices)?;
+    Ok(boruvka(edges, number_of_vertices))
+}
+
+// Spanning tree with the largest total cost (Kruskal's algorithm, taking the most expensive edges first)
+// Vertices must be in 0..number_of_vertices (anything else panics, see try_maximum_spanning_tree)
+pub fn maximum_spanning_tree(edges: Vec<Edge>, number_of_vertices: i64) -> (i64, Vec<Edge>) {
+    let negated = edges
+        .into_iter()
+        .map(|edge| Edge::new(edge.source, edge.destination, -edge.cost))
+        .collect();
+    let (total_cost, final_edges) = kruskal(negated, number_of_vertices);
+    let final_edges = final_edges
+        .into_iter()
+        .map(|edge| Edge::new(edge.source, edge.destination, -edge.cost))
+        .collect();
+    (-total_cost, final_edges)
+}
+
+// maximum_spanning_tree, returning an error for the first invalid edge (as try_kruskal does)
+pub fn try_maximum_spanning_tree(edges: Vec<Edge>, number_of_vertices: i64) -> Result<(i64, Vec<Edge>), GraphError> {
+    check_edges(&edges, number_of_vertices)?;
+    Ok(maximum_spanning_tree(edges, number_of_vertices))
+}
+
+/*
+
+Second-best MST & sensitivity analysis
+
+Adding a non-tree edge (u, v) to a spanning tree makes exactly one cycle: the edge plus the tree path from u to v.
+Removing any tree edge on that path gives another spanning tree, so:
+
+* The second-best MST swaps in one non-tree edge & swaps out the most expensive tree edge on its path,
+  choosing the swap that adds the least cost.
+* A non-tree edge can fall in cost until it is as cheap as the most expensive tree edge on its path.
+  Any cheaper & it would replace that edge.
+* A tree edge can rise in cost until it is as expensive as the cheapest non-tree edge whose path crosses it.
+  With no such edge (the tree edge is a bridge) it can rise forever.
+
+*/
+
+// How much an edge's cost can change before the minimum spanning tree changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sensitivity {
+    pub edge: Edge,
+    pub in_tree: bool,
+    // Tree edges: how much the cost can rise. Non-tree edges: how much the cost can fall
+    // None means the cost can change by any amount
+    pub slack: Option<i64>,
+}
+
+// Positions (in edges) of the edges Kruskal's algorithm chooses
+// The sort is stable, so equal cost edges are chosen in list order (as kruskal does)
+fn tree_positions(edges: &[Edge], number_of_vertices: i64) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_by_key(|i| edges[*i].cost);
+    let mut sets = DisjointSet::new(number_of_vertices as usize);
+    order
+        .into_iter()
+        .filter(|i| sets.union(edges[*i].source as usize, edges[*i].destination as usize))
+        .collect()
+}
+
+// A spanning forest with a root for each tree, so that paths between vertices can be followed
+struct RootedForest {
+    parent: Vec<usize>,
+    // Position (in edges) of the edge from each vertex to its parent (unused for roots)
+    parent_edge: Vec<usize>,
+    depth: Vec<usize>,
+}
+
+impl RootedForest {
+    fn new(edges: &[Edge], tree: &[usize], number_of_vertices: usize) -> RootedForest {
+        let mut touching: Vec<Vec<usize>> = vec![Vec::new(); number_of_vertices];
+        for i in tree {
+            touching[edges[*i].source as usize].push(*i);
+            touching[edges[*i].destination as usize].push(*i);
+        }
+
+        let mut forest = RootedForest {
+            parent: (0..number_of_vertices).collect(),
+            parent_edge: vec![0; number_of_vertices],
+            depth: vec![0; number_of_vertices],
+        };
+        let mut visited = vec![false; number_of_vertices];
+        for root in 0..number_of_vertices {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut stack = vec![root];
+            while let Some(v) = stack.pop() {
+                for i in touching[v].iter() {
+                    let edge = &edges[*i];
+                    let u = if edge.source as usize == v { edge.destination } else { edge.source } as usize;
+                    if !visited[u] {
+                        visited[u] = true;
+                        forest.parent[u] = v;
+                        forest.parent_edge[u] = *i;
+                        forest.depth[u] = forest.depth[v] + 1;
+                        stack.push(u);
+                    }
+                }
+            }
+        }
+        forest
+    }
+
+    // Positions of the tree edges on the path between u & v (which must be in the same tree)
+    fn path(&self, mut u: usize, mut v: usize) -> Vec<usize> {
+        let mut path = Vec::new();
+        while u != v {
+            // Step up from the deeper vertex
+            if self.depth[u] < self.depth[v] {
+                std::mem::swap(&mut u, &mut v);
+            }
+            path.push(self.parent_edge[u]);
+            u = self.parent[u];
+        }
+        path
+    }
+}
+
+// Most expensive edge (as a position) on the tree path between the ends of each non-tree edge
+// Self loops are left out because they don't make a path
+fn swaps(edges: &[Edge], tree: &[usize], forest: &RootedForest) -> Vec<(usize, usize)> {
+    let mut in_tree = vec![false; edges.len()];
+    tree.iter().for_each(|i| in_tree[*i] = true);
+    (0..edges.len())
+        .filter(|i| !in_tree[*i] && edges[*i].source != edges[*i].destination)
+        .map(|i| {
+            let path = forest.path(edges[i].source as usize, edges[i].destination as usize);
+            let heaviest = path.into_iter().max_by_key(|j| edges[*j].cost).unwrap();
+            (i, heaviest)
+        })
+        .collect()
+}
+
+// The cheapest spanning tree (or forest) other than the one kruskal finds, or None if there is no other
+// Its cost can equal the minimum if the graph has more than one minimum spanning tree
+// Vertices must be in 0..number_of_vertices (anything else panics, see try_second_best_mst)
+pub fn second_best_mst(edges: Vec<Edge>, number_of_vertices: i64) -> Option<(i64, Vec<Edge>)> {
+    let tree = tree_positions(&edges, number_of_vertices);
+    let forest = RootedForest::new(&edges, &tree, number_of_vertices as usize);
+    let total_cost: i64 = tree.iter().map(|i| edges[*i].cost).sum();
+
+    // Choose the swap that adds the least cost
+    let (added, removed) = swaps(&edges, &tree, &forest)
+        .into_iter()
+        .min_by_key(|(added, removed)| edges[*added].cost - edges[*removed].cost)?;
+
+    let mut final_edges: Vec<Edge> = tree
+        .iter()
+        .filter(|i| **i != removed)
+        .map(|i| edges[*i].clone())
+        .collect();
+    final_edges.push(edges[added].clone());
+    Some((total_cost - edges[removed].cost + edges[added].cost, final_edges))
+}
+
+// second_best_mst, returning an error for the first invalid edge (as try_kruskal does)
+pub fn try_second_best_mst(
+    edges: Vec<Edge>,
+    number_of_vertices: i64,
+) -> Result<Option<(i64, Vec<Edge>)>, GraphError> {
+    check_edges(&edges, number_of_vertices)?;
+    Ok(second_best_mst(edges, number_of_vertices))
+}
+
+// Sensitivity of every edge (in the same order as edges) for the tree kruskal finds
+// Vertices must be in 0..number_of_vertices (anything else panics, see try_mst_sensitivity)
+pub fn mst_sensitivity(edges: Vec<Edge>, number_of_vertices: i64) -> Vec<Sensitivity> {
+    let tree = tree_positions(&edges, number_of_vertices);
+    let forest = RootedForest::new(&edges, &tree, number_of_vertices as usize);
+
+    let mut in_tree = vec![false; edges.len()];
+    tree.iter().for_each(|i| in_tree[*i] = true);
+    let mut slack: Vec<Option<i64>> = vec![None; edges.len()];
+
+    for (added, removed) in swaps(&edges, &tree, &forest) {
+        // The non-tree edge can fall to the cost of the most expensive edge on its path
+        slack[added] = Some(edges[added].cost - edges[removed].cost);
+        // Each tree edge on the path can rise to the cost of the non-tree edge
+        let path = forest.path(edges[added].source as usize, edges[added].destination as usize);
+        for i in path {
+            let rise = edges[added].cost - edges[i].cost;
+            if slack[i].is_none_or(|s| rise < s) {
+                slack[i] = Some(rise);
+            }
+        }
+    }
+
+    edges
+        .into_iter()
+        .zip(in_tree)
+        .zip(slack)
+        .map(|((edge, in_tree), slack)| Sensitivity { edge, in_tree, slack })
+        .collect()
+}
+
+// mst_sensitivity, returning an error for the first invalid edge (as try_kruskal does)
+pub fn try_mst_sensitivity(edges: Vec<Edge>, number_of_vertices: i64) -> Result<Vec<Sensitivity>, GraphError> {
+    check_edges(&edges, number_of_vertices)?;
+    Ok(mst_sensitivity(edges, number_of_vertices))
+}
+
+/*
+
+Incremental MST
+
+Keeps a minimum spanning forest up to date as edges are added, without rerunning Kruskal's algorithm.
+
+When edge (u, v) is added:
+
+* If u & v are in different trees, the edge joins them.
+* Otherwise the edge makes a cycle with the tree path from u to v. The most expensive edge on the cycle
+  can't be in the minimum spanning tree, so if that is a tree edge (more expensive than the new edge)
+  it is swapped for the new edge. If not, the new edge is left out.
+
+Finding the path takes O(number of vertices) per edge, instead of O(E log E) to rerun kruskal.
+
+*/
+pub struct IncrementalMst {
+    // Tree edges touching each vertex as (neighbour, cost)
+    adjacency: Vec<Vec<(usize, i64)>>,
+    total_cost: i64,
+    // Number of trees in the forest
+    trees: usize,
+}
+
+impl IncrementalMst {
+    // Vertices 0..number_of_vertices with no edges
+    pub fn new(number_of_vertices: i64) -> Result<Self, GraphError> {
+        check_vertices(number_of_vertices, Vec::new())?;
+        Ok(Self {
+            adjacency: vec![Vec::new(); number_of_vertices as usize],
+            total_cost: 0,
+            trees: number_of_vertices as usize,
+        })
+    }
+
+    // Start from the minimum spanning forest of edges (found with try_kruskal)
+    pub fn from_edges(edges: Vec<Edge>, number_of_vertices: i64) -> Result<Self, GraphError> {
+        let (_, final_edges) = try_kruskal(edges, number_of_vertices)?;
+        let mut mst = Self::new(number_of_vertices)?;
+        for edge in final_edges {
+            mst.insert(edge)?;
+        }
+        Ok(mst)
+    }
+
+    // Check that u & v are vertices of the forest
+    fn check(&self, u: i64, v: i64) -> Result<(usize, usize), GraphError> {
+        let number_of_vertices = self.adjacency.len() as i64;
+        for vertex in [u, v] {
+            if !(0..number_of_vertices).contains(&vertex) {
+                return Err(GraphError::VertexOutOfRange {
+                    vertex,
+                    number_of_vertices,
+                });
+            }
+        }
+        Ok((u as usize, v as usize))
+    }
+
+    pub fn total_cost(&self) -> i64 {
+        self.total_cost
+    }
+
+    // Number of trees in the forest (1 once every vertex is connected)
+    pub fn trees(&self) -> usize {
+        self.trees
+    }
+
+    // Tree edges, each with source < destination, sorted by source then destination
+    pub fn edges(&self) -> Vec<Edge> {
+        let mut edges = Vec::new();
+        for (source, neighbours) in self.adjacency.iter().enumerate() {
+            for (destination, cost) in neighbours.iter().filter(|(d, _)| source < *d) {
+                edges.push(Edge::new(source as i64, *destination as i64, *cost));
+            }
+        }
+        edges.sort_by_key(|edge| (edge.source, edge.destination));
+        edges
+    }
+
+    // Tree path from u to v as a list of (vertex, next vertex, cost), or None if they are in different trees
+    fn path(&self, u: usize, v: usize) -> Option<Vec<(usize, usize, i64)>> {
+        // Depth first search from u, remembering how each vertex was reached
+        let mut reached_from: Vec<Option<(usize, i64)>> = vec![None; self.adjacency.len()];
+        let mut stack = vec![u];
+        reached_from[u] = Some((u, 0));
+        while let Some(current) = stack.pop() {
+            if current == v {
+                break;
+            }
+            for (next, cost) in self.adjacency[current].iter() {
+                if reached_from[*next].is_none() {
+                    reached_from[*next] = Some((current, *cost));
+                    stack.push(*next);
+                }
+            }
+        }
+
+        reached_from[v]?;
+        let mut path = Vec::new();
+        let mut current = v;
+        while current != u {
+            let (previous, cost) = reached_from[current].unwrap();
+            path.push((previous, current, cost));
+            current = previous;
+        }
+        Some(path)
+    }
+
+    pub fn connected(&self, u: i64, v: i64) -> Result<bool, GraphError> {
+        let (u, v) = self.check(u, v)?;
+        Ok(self.path(u, v).is_some())
+    }
+
+    fn remove(&mut self, u: usize, v: usize, cost: i64) {
+        for (from, to) in [(u, v), (v, u)] {
+            let position = self.adjacency[from].iter().position(|n| *n == (to, cost)).unwrap();
+            self.adjacency[from].swap_remove(position);
+        }
+        self.total_cost -= cost;
+    }
+
+    // Add an edge & update the forest
+    // Returns the edge that is not in the forest afterwards (the new edge, or the tree edge it replaced),
+    // or None if the new edge joined two trees
+    pub fn insert(&mut self, edge: Edge) -> Result<Option<Edge>, GraphError> {
+        let (u, v) = self.check(edge.source, edge.destination)?;
+        let path = match self.path(u, v) {
+            Some(path) => path,
+            None => {
+                self.trees -= 1;
+                self.link(u, v, edge.cost);
+                return Ok(None);
+            }
+        };
+
+        // Most expensive tree edge on the cycle (a self loop has none)
+        Ok(match path.into_iter().max_by_key(|(_, _, cost)| *cost) {
+            Some((a, b, cost)) if cost > edge.cost => {
+                self.remove(a, b, cost);
+                self.link(u, v, edge.cost);
+                Some(Edge::new(a as i64, b as i64, cost))
+            }
+            _ => Some(edge),
+        })
+    }
+
+    fn link(&mut self, u: usize, v: usize, cost: i64) {
+        self.adjacency[u].push((v, cost));
+        self.adjacency[v].push((u, cost));
+        self.total_cost += cost;
+    }
+}
+
+// Minimum spanning forest of a graph with named nodes
+pub struct SpanningForest {
+    pub total_cost: i64,
+    // Every node of the graph & the edges chosen
+    pub forest: UndirectedGraph,
+    // Number of trees in the forest (one per connected component)
+    pub trees: usize,
+}
+
+impl SpanningForest {
+    // Is the forest a single tree (i.e. was the graph connected)?
+    pub fn is_spanning_tree(&self) -> bool {
+        self.trees == 1
+    }
+}
+
+// Kruskal's algorithm on any undirected Graph
+// Returns GraphError::NotUndirected for a directed graph
+pub fn minimum_spanning_forest<G: Graph>(graph: &G) -> Result<SpanningForest, GraphError> {
+    if graph.is_directed() {
+        return Err(GraphError::NotUndirected);
+    }
+
+    // Number the nodes (sorted, so the numbering is the same on every run)
+    let mut names: Vec<&String> = graph.nodes().into_iter().collect();
+    names.sort();
+    let index: HashMap<&String, i64> = names.iter().enumerate().map(|(i, n)| (*n, i as i64)).collect();
+
+    // Undirected edges are stored in both directions, so only keep one of them
+    let mut edges: Vec<Edge> = graph
+        .edges()
+        .into_iter()
+        .filter(|(from_node, to_node, _)| from_node <= to_node)
+        .map(|(from_node, to_node, weight)| Edge::new(index[from_node], index[to_node], weight as i64))
+        .collect();
+    edges.sort_by_key(|edge| (edge.source, edge.destination));
+
+    let (total_cost, tree_edges) = kruskal(edges, names.len() as i64);
+
+    let mut forest = UndirectedGraph::new();
+    for name in names.iter() {
+        forest.add_node(name);
+    }
+    for edge in tree_edges.iter() {
+        forest.add_edge((
+            names[edge.source as usize],
+            names[edge.destination as usize],
+            edge.cost as i32,
+        ));
+    }
+
+    Ok(SpanningForest {
+        total_cost,
+        forest,
+        // Each tree edge joins two trees into one
+        trees: names.len() - tree_edges.len(),
+    })
+}
+
+// Minimum spanning tree (total cost & tree) of a connected undirected Graph
+// Returns GraphError::Disconnected if there is no spanning tree (or EmptyGraph if there are no nodes)
+pub fn minimum_spanning_tree<G: Graph>(graph: &G) -> Result<(i64, UndirectedGraph), GraphError> {
+    let result = minimum_spanning_forest(graph)?;
+    match result.trees {
+        0 => return Err(GraphError::EmptyGraph),
+        1 => (),
+        _ => return Err(GraphError::Disconnected),
+    }
+    Ok((result.total_cost, result.forest))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use graph::{DirectedGraph, MultiGraph};
+    use test_random::Lcg;
 
     #[test]
     fn test_seven_vertices_eleven_edges() {
@@ -191,4 +1350,701 @@ mod tests {
         assert_eq!(actual_total_cost, expected_total_cost);
         assert_eq!(actual_final_edges, expected_used_edges);
     }
+
+    #[test]
+    fn test_disjoint_set() {
+        assert!(DisjointSet::new(0).is_empty());
+
+        let mut sets = DisjointSet::new(6);
+        assert_eq!(sets.len(), 6);
+        assert_eq!(sets.count(), 6);
+        assert!(!sets.same_set(0, 1));
+
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        // Already in the same set
+        assert!(!sets.union(0, 2));
+
+        assert_eq!(sets.count(), 3);
+        assert!(sets.same_set(0, 3));
+        assert!(!sets.same_set(0, 4));
+        assert_eq!(sets.find(2), sets.find(0));
+        assert_eq!(sets.set_size(3), 4);
+        assert_eq!(sets.set_size(5), 1);
+        assert_eq!(sets.members(2).collect::<Vec<usize>>(), vec![0, 1, 2, 3]);
+        assert_eq!(sets.sets(), vec![vec![0, 1, 2, 3], vec![4], vec![5]]);
+
+        let x = sets.add();
+        assert_eq!(x, 6);
+        assert!(sets.union(x, 4));
+        assert_eq!(sets.count(), 3);
+        assert_eq!(sets.members(6).collect::<Vec<usize>>(), vec![4, 6]);
+    }
+
+    #[test]
+    fn test_disjoint_set_union_by_size() {
+        let mut sets = DisjointSet::new(4);
+        sets.union(0, 1);
+        sets.union(0, 2);
+        // The single element joins the larger set, so the larger root stays the root
+        let root = sets.find(0);
+        sets.union(3, 0);
+        assert_eq!(sets.find(3), root);
+
+        // A long chain of unions still finds the same root for every element
+        let mut sets = DisjointSet::new(1000);
+        for i in 1..1000 {
+            sets.union(i - 1, i);
+        }
+        let root = sets.find(0);
+        assert!((0..1000).all(|i| sets.find(i) == root));
+        assert_eq!(sets.count(), 1);
+        assert_eq!(sets.set_size(999), 1000);
+    }
+
+    #[test]
+    fn test_keyed_disjoint_set() {
+        let mut sets: KeyedDisjointSet<String> = KeyedDisjointSet::new();
+        assert!(sets.is_empty());
+        assert!(sets.add(String::from("a")));
+        assert!(!sets.add(String::from("a")));
+
+        // Unknown labels are added by union
+        assert!(sets.union(String::from("a"), String::from("b")));
+        assert!(sets.union(String::from("c"), String::from("d")));
+        assert!(!sets.union(String::from("b"), String::from("a")));
+
+        assert_eq!(sets.len(), 4);
+        assert_eq!(sets.count(), 2);
+        assert!(sets.contains(&String::from("d")));
+        assert!(sets.same_set(&String::from("a"), &String::from("b")));
+        assert!(!sets.same_set(&String::from("a"), &String::from("c")));
+        assert!(!sets.same_set(&String::from("a"), &String::from("z")));
+        assert_eq!(sets.find(&String::from("z")), None);
+        assert_eq!(sets.set_size(&String::from("c")), Some(2));
+        assert_eq!(
+            sets.members(&String::from("d")).unwrap().collect::<Vec<&String>>(),
+            vec!["c", "d"]
+        );
+        assert!(sets.members(&String::from("z")).is_none());
+
+        sets.union(String::from("b"), String::from("c"));
+        assert_eq!(sets.count(), 1);
+        let root = sets.find(&String::from("a")).cloned();
+        assert_eq!(sets.find(&String::from("d")), root.as_ref());
+        assert_eq!(sets.sets(), vec![vec!["a", "b", "c", "d"]]);
+    }
+
+    // The graph from test_seven_vertices_eleven_edges with named nodes
+    fn named_graph() -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        graph.add_edge(("a", "b", 7));
+        graph.add_edge(("a", "d", 5));
+        graph.add_edge(("b", "c", 8));
+        graph.add_edge(("b", "d", 9));
+        graph.add_edge(("b", "e", 7));
+        graph.add_edge(("c", "e", 5));
+        graph.add_edge(("d", "e", 15));
+        graph.add_edge(("d", "f", 6));
+        graph.add_edge(("e", "f", 8));
+        graph.add_edge(("e", "g", 9));
+        graph.add_edge(("f", "g", 11));
+        graph
+    }
+
+    fn sorted_edges(graph: &UndirectedGraph) -> Vec<(String, String, i32)> {
+        let mut edges: Vec<(String, String, i32)> = graph
+            .edges()
+            .into_iter()
+            .filter(|(a, b, _)| a <= b)
+            .map(|(a, b, w)| (a.to_string(), b.to_string(), w))
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_minimum_spanning_tree_of_graph() {
+        let (total_cost, tree) = minimum_spanning_tree(&named_graph()).unwrap();
+
+        assert_eq!(total_cost, 39);
+        assert_eq!(tree.nodes().len(), 7);
+        assert_eq!(
+            sorted_edges(&tree),
+            vec![
+                (String::from("a"), String::from("b"), 7),
+                (String::from("a"), String::from("d"), 5),
+                (String::from("b"), String::from("e"), 7),
+                (String::from("c"), String::from("e"), 5),
+                (String::from("d"), String::from("f"), 6),
+                (String::from("e"), String::from("g"), 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest() {
+        let mut graph = named_graph();
+        // A second component & a node on its own
+        graph.add_edge(("x", "y", 2));
+        graph.add_edge(("y", "z", 1));
+        graph.add_edge(("x", "z", 4));
+        graph.add_node("lonely");
+
+        let result = minimum_spanning_forest(&graph).unwrap();
+
+        assert_eq!(result.trees, 3);
+        assert!(!result.is_spanning_tree());
+        assert_eq!(result.total_cost, 39 + 3);
+        assert_eq!(result.forest.nodes().len(), 11);
+        assert_eq!(result.forest.edges().len(), 2 * 8);
+        assert_eq!(result.forest.neighbours("lonely").unwrap(), &vec![]);
+
+        assert_eq!(minimum_spanning_tree(&graph).err(), Some(GraphError::Disconnected));
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest_of_multigraph() {
+        // Only the cheaper of two parallel edges is used & self loops are ignored
+        let mut graph = MultiGraph::new();
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("a", "b", 2));
+        graph.add_edge(("b", "b", 1));
+        graph.add_edge(("b", "c", 3));
+
+        let result = minimum_spanning_forest(&graph).unwrap();
+
+        assert!(result.is_spanning_tree());
+        assert_eq!(result.total_cost, 5);
+        assert_eq!(
+            sorted_edges(&result.forest),
+            vec![(String::from("a"), String::from("b"), 2), (String::from("b"), String::from("c"), 3)]
+        );
+    }
+
+    #[test]
+    fn test_minimum_spanning_forest_errors() {
+        let mut directed = DirectedGraph::new();
+        directed.add_edge(("a", "b", 1));
+        assert_eq!(minimum_spanning_forest(&directed).err(), Some(GraphError::NotUndirected));
+
+        let empty = UndirectedGraph::new();
+        assert_eq!(minimum_spanning_forest(&empty).unwrap().trees, 0);
+        assert_eq!(minimum_spanning_tree(&empty).err(), Some(GraphError::EmptyGraph));
+    }
+
+    // A pseudo-random list of edges (see test_random.rs) so tests are repeatable
+    fn random_edges(number_of_vertices: i64, number_of_edges: usize, max_cost: i64) -> Vec<Edge> {
+        let mut random = Lcg::new(7);
+        let mut next = |n: i64| random.below(n as u64) as i64;
+        (0..number_of_edges)
+            .map(|_| Edge::new(next(number_of_vertices), next(number_of_vertices), next(max_cost) + 1))
+            .collect()
+    }
+
+    fn sorted(edges: Vec<Edge>) -> Vec<(i64, i64, i64)> {
+        let mut edges: Vec<(i64, i64, i64)> = edges.into_iter().map(|e| (e.source, e.destination, e.cost)).collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn test_prim_and_boruvka_match_kruskal() {
+        // Sparse, dense, disconnected & with many equal costs
+        let cases = [(50, 60, 100), (30, 400, 1000), (80, 50, 10), (40, 200, 3)];
+        for (number_of_vertices, number_of_edges, max_cost) in cases.iter() {
+            let edges = random_edges(*number_of_vertices, *number_of_edges, *max_cost);
+
+            let (kruskal_cost, kruskal_edges) = kruskal(edges.clone(), *number_of_vertices);
+            let (prim_cost, prim_edges) = prim(edges.clone(), *number_of_vertices);
+            let (boruvka_cost, boruvka_edges) = boruvka(edges.clone(), *number_of_vertices);
+
+            assert_eq!(prim_cost, kruskal_cost);
+            assert_eq!(boruvka_cost, kruskal_cost);
+            assert_eq!(prim_edges.len(), kruskal_edges.len());
+            assert_eq!(boruvka_edges.len(), kruskal_edges.len());
+            // Each result is a forest: no edge joins two vertices that are already connected
+            for result in [&prim_edges, &boruvka_edges] {
+                let mut sets = DisjointSet::new(*number_of_vertices as usize);
+                assert!(result.iter().all(|e| sets.union(e.source as usize, e.destination as usize)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_prim_and_boruvka_with_distinct_costs() {
+        // With distinct costs the minimum spanning tree is unique, so all three choose the same edges
+        let edges: Vec<Edge> = random_edges(60, 300, 1)
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| Edge::new(e.source, e.destination, (i as i64 * 7919) % 300))
+            .collect();
+
+        let (kruskal_cost, kruskal_edges) = kruskal(edges.clone(), 60);
+        let (prim_cost, prim_edges) = prim(edges.clone(), 60);
+        let (boruvka_cost, boruvka_edges) = boruvka(edges.clone(), 60);
+
+        assert_eq!(prim_cost, kruskal_cost);
+        assert_eq!(boruvka_cost, kruskal_cost);
+        assert_eq!(sorted(prim_edges), sorted(kruskal_edges.clone()));
+        assert_eq!(sorted(boruvka_edges), sorted(kruskal_edges));
+    }
+
+    #[test]
+    fn test_prim_and_boruvka_small_graphs() {
+        let edges = vec![Edge::new(0, 1, 1), Edge::new(1, 2, 2), Edge::new(2, 3, 3), Edge::new(0, 2, 1)];
+
+        assert_eq!(prim(edges.clone(), 4).0, 5);
+        assert_eq!(boruvka(edges.clone(), 4).0, 5);
+        assert_eq!(prim(Vec::new(), 0), (0, Vec::new()));
+        assert_eq!(boruvka(Vec::new(), 0), (0, Vec::new()));
+        // A self loop is never part of a tree
+        assert_eq!(prim(vec![Edge::new(0, 0, 1)], 1), (0, Vec::new()));
+        assert_eq!(boruvka(vec![Edge::new(0, 0, 1)], 1), (0, Vec::new()));
+    }
+
+    #[test]
+    fn test_try_kruskal() {
+        let edges = vec![Edge::new(0, 1, 7), Edge::new(1, 2, 5), Edge::new(0, 2, 9)];
+        assert_eq!(
+            try_kruskal(edges.clone(), 3),
+            Ok((12, vec![Edge::new(1, 2, 5), Edge::new(0, 1, 7)]))
+        );
+        assert_eq!(try_kruskal(Vec::new(), 0), Ok((0, Vec::new())));
+
+        assert_eq!(
+            try_kruskal(edges.clone(), 2),
+            Err(GraphError::ItemVertexOutOfRange {
+                position: 1,
+                vertex: 2,
+                number_of_vertices: 2,
+            })
+        );
+        assert_eq!(
+            try_kruskal(vec![Edge::new(0, -1, 3)], 2),
+            Err(GraphError::ItemVertexOutOfRange {
+                position: 0,
+                vertex: -1,
+                number_of_vertices: 2,
+            })
+        );
+        assert!(matches!(
+            try_kruskal(edges.clone(), 0),
+            Err(GraphError::ItemVertexOutOfRange { position: 0, .. })
+        ));
+        assert_eq!(try_kruskal(edges, -1), Err(GraphError::NegativeVertexCount(-1)));
+    }
+
+    #[test]
+    fn test_checked_variants() {
+        let edges = seven_vertex_edges();
+        // Edge 9 is 4 - 6
+        let out_of_range = Some(GraphError::ItemVertexOutOfRange {
+            position: 9,
+            vertex: 6,
+            number_of_vertices: 6,
+        });
+        let negative = Some(GraphError::NegativeVertexCount(-7));
+
+        assert_eq!(try_prim(edges.clone(), 7), Ok(prim(edges.clone(), 7)));
+        assert_eq!(try_prim(edges.clone(), 6).err(), out_of_range);
+        assert_eq!(try_prim(edges.clone(), -7).err(), negative);
+
+        assert_eq!(try_boruvka(edges.clone(), 7), Ok(boruvka(edges.clone(), 7)));
+        assert_eq!(try_boruvka(edges.clone(), 6).err(), out_of_range);
+        assert_eq!(try_boruvka(edges.clone(), -7).err(), negative);
+
+        assert_eq!(
+            try_maximum_spanning_tree(edges.clone(), 7),
+            Ok(maximum_spanning_tree(edges.clone(), 7))
+        );
+        assert_eq!(try_maximum_spanning_tree(edges.clone(), 6).err(), out_of_range);
+        assert_eq!(try_maximum_spanning_tree(edges.clone(), -7).err(), negative);
+
+        assert_eq!(try_second_best_mst(edges.clone(), 7), Ok(second_best_mst(edges.clone(), 7)));
+        assert_eq!(try_second_best_mst(edges.clone(), 6).err(), out_of_range);
+        assert_eq!(try_second_best_mst(edges.clone(), -7).err(), negative);
+
+        assert_eq!(try_mst_sensitivity(edges.clone(), 7), Ok(mst_sensitivity(edges.clone(), 7)));
+        assert_eq!(try_mst_sensitivity(edges.clone(), 6).err(), out_of_range);
+        assert_eq!(try_mst_sensitivity(edges, -7).err(), negative);
+        assert_eq!(
+            try_mst_sensitivity(vec![Edge::new(-1, 0, 1)], 2).err(),
+            Some(GraphError::ItemVertexOutOfRange {
+                position: 0,
+                vertex: -1,
+                number_of_vertices: 2,
+            })
+        );
+    }
+
+    fn seven_vertex_edges() -> Vec<Edge> {
+        vec![
+            Edge::new(0, 1, 7),
+            Edge::new(0, 3, 5),
+            Edge::new(1, 2, 8),
+            Edge::new(1, 3, 9),
+            Edge::new(1, 4, 7),
+            Edge::new(2, 4, 5),
+            Edge::new(3, 4, 15),
+            Edge::new(3, 5, 6),
+            Edge::new(4, 5, 8),
+            Edge::new(4, 6, 9),
+            Edge::new(5, 6, 11),
+        ]
+    }
+
+    #[test]
+    fn test_maximum_spanning_tree() {
+        let (total_cost, final_edges) = maximum_spanning_tree(seven_vertex_edges(), 7);
+
+        assert_eq!(total_cost, 59);
+        assert_eq!(
+            final_edges,
+            vec![
+                Edge::new(3, 4, 15),
+                Edge::new(5, 6, 11),
+                Edge::new(1, 3, 9),
+                Edge::new(4, 6, 9),
+                Edge::new(1, 2, 8),
+                Edge::new(0, 1, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_second_best_mst() {
+        let (total_cost, final_edges) = second_best_mst(seven_vertex_edges(), 7).unwrap();
+
+        // Swap (1, 2, 8) in for (1, 4, 7)
+        assert_eq!(total_cost, 40);
+        assert_eq!(final_edges.len(), 6);
+        assert!(final_edges.contains(&Edge::new(1, 2, 8)));
+        assert!(!final_edges.contains(&Edge::new(1, 4, 7)));
+
+        // Check against every spanning tree, found by removing one edge from the graph at a time
+        // (the second-best MST differs from the MST by exactly one edge)
+        let (best, tree) = kruskal(seven_vertex_edges(), 7);
+        let mut expected = i64::MAX;
+        for removed in tree.iter() {
+            let edges = seven_vertex_edges().into_iter().filter(|e| e != removed).collect();
+            let (cost, others) = kruskal(edges, 7);
+            if others.len() == 6 {
+                expected = expected.min(cost);
+            }
+        }
+        assert!(expected > best);
+        assert_eq!(total_cost, expected);
+
+        // A tree has no other spanning tree
+        assert_eq!(second_best_mst(vec![Edge::new(0, 1, 1), Edge::new(1, 2, 1)], 3), None);
+        // A self loop can't replace a tree edge
+        assert_eq!(second_best_mst(vec![Edge::new(0, 1, 1), Edge::new(1, 1, 1)], 2), None);
+    }
+
+    #[test]
+    fn test_mst_sensitivity() {
+        let sensitivity = mst_sensitivity(seven_vertex_edges(), 7);
+
+        assert_eq!(sensitivity.len(), 11);
+        let slack: Vec<(bool, Option<i64>)> = sensitivity.iter().map(|s| (s.in_tree, s.slack)).collect();
+        assert_eq!(
+            slack,
+            vec![
+                (true, Some(1)),   // (0, 1, 7): replaced by (4, 5, 8)
+                (true, Some(3)),   // (0, 3, 5): replaced by (4, 5, 8)
+                (false, Some(1)),  // (1, 2, 8): can fall to 7
+                (false, Some(2)),  // (1, 3, 9): can fall to 7
+                (true, Some(1)),   // (1, 4, 7): replaced by (1, 2, 8)
+                (true, Some(3)),   // (2, 4, 5): replaced by (1, 2, 8)
+                (false, Some(8)),  // (3, 4, 15): can fall to 7
+                (true, Some(2)),   // (3, 5, 6): replaced by (4, 5, 8)
+                (false, Some(1)),  // (4, 5, 8): can fall to 7
+                (true, Some(2)),   // (4, 6, 9): replaced by (5, 6, 11)
+                (false, Some(2)),  // (5, 6, 11): can fall to 9
+            ]
+        );
+        assert_eq!(sensitivity[0].edge, Edge::new(0, 1, 7));
+
+        // A bridge can rise by any amount, as can a self loop fall
+        let sensitivity = mst_sensitivity(vec![Edge::new(0, 1, 3), Edge::new(1, 1, 2)], 2);
+        assert_eq!(sensitivity[0].slack, None);
+        assert!(sensitivity[0].in_tree);
+        assert_eq!(sensitivity[1].slack, None);
+        assert!(!sensitivity[1].in_tree);
+    }
+
+    #[test]
+    fn test_tied_costs_give_kruskals_tree() {
+        // Every edge of the square costs the same, so any three make a minimum spanning tree
+        let square = vec![Edge::new(0, 1, 1), Edge::new(1, 2, 1), Edge::new(2, 3, 1), Edge::new(3, 0, 1)];
+        assert_eq!(kruskal(square.clone(), 4).1, square[..3].to_vec());
+
+        let mut cases = vec![(square, 4)];
+        for n in [5, 10, 20].iter() {
+            cases.push((random_edges(*n, 3 * *n as usize, 2), *n));
+        }
+        for (edges, number_of_vertices) in cases {
+            let (_, tree) = kruskal(edges.clone(), number_of_vertices);
+            let in_tree: Vec<Edge> = mst_sensitivity(edges, number_of_vertices)
+                .into_iter()
+                .filter(|s| s.in_tree)
+                .map(|s| s.edge)
+                .collect();
+            assert_eq!(sorted(in_tree), sorted(tree));
+        }
+    }
+
+    #[test]
+    fn test_incremental_mst() {
+        let mut mst = IncrementalMst::new(4).unwrap();
+        assert_eq!(mst.trees(), 4);
+
+        assert_eq!(mst.insert(Edge::new(0, 1, 5)), Ok(None));
+        assert_eq!(mst.insert(Edge::new(1, 2, 4)), Ok(None));
+        assert_eq!(mst.insert(Edge::new(2, 3, 3)), Ok(None));
+        assert_eq!(mst.total_cost(), 12);
+        assert_eq!(mst.trees(), 1);
+
+        // Makes the cycle 0 - 1 - 2 - 0 & replaces (0, 1, 5)
+        assert_eq!(mst.insert(Edge::new(0, 2, 1)), Ok(Some(Edge::new(0, 1, 5))));
+        assert_eq!(mst.total_cost(), 8);
+        // More expensive than every edge on its cycle, so it is left out
+        assert_eq!(mst.insert(Edge::new(3, 0, 9)), Ok(Some(Edge::new(3, 0, 9))));
+        assert_eq!(mst.insert(Edge::new(1, 1, 0)), Ok(Some(Edge::new(1, 1, 0))));
+
+        assert_eq!(mst.total_cost(), 8);
+        assert_eq!(
+            mst.edges(),
+            vec![Edge::new(0, 2, 1), Edge::new(1, 2, 4), Edge::new(2, 3, 3)]
+        );
+        assert_eq!(mst.connected(0, 3), Ok(true));
+    }
+
+    #[test]
+    fn test_incremental_mst_errors() {
+        assert_eq!(IncrementalMst::new(-1).err(), Some(GraphError::NegativeVertexCount(-1)));
+        assert!(matches!(
+            IncrementalMst::from_edges(vec![Edge::new(0, 1, 2), Edge::new(1, 3, 2)], 3),
+            Err(GraphError::ItemVertexOutOfRange { position: 1, vertex: 3, number_of_vertices: 3 })
+        ));
+
+        let mut mst = IncrementalMst::new(3).unwrap();
+        let out_of_range = |vertex| GraphError::VertexOutOfRange { vertex, number_of_vertices: 3 };
+        assert_eq!(mst.insert(Edge::new(0, 3, 1)), Err(out_of_range(3)));
+        assert_eq!(mst.insert(Edge::new(-1, 2, 1)), Err(out_of_range(-1)));
+        assert_eq!(mst.connected(1, 7), Err(out_of_range(7)));
+        // A single vertex isn't an item of a list, so the message has no position
+        assert_eq!(out_of_range(7).to_string(), "vertex 7 is outside 0..3");
+
+        // Nothing changed
+        assert_eq!((mst.trees(), mst.total_cost()), (3, 0));
+    }
+
+    #[test]
+    fn test_incremental_mst_matches_kruskal() {
+        let edges = random_edges(40, 200, 50);
+        let mut mst = IncrementalMst::new(40).unwrap();
+
+        for (i, edge) in edges.iter().enumerate() {
+            mst.insert(edge.clone()).unwrap();
+
+            let (total_cost, final_edges) = kruskal(edges[..=i].to_vec(), 40);
+            assert_eq!(mst.total_cost(), total_cost);
+            assert_eq!(mst.trees(), 40 - final_edges.len());
+        }
+
+        let from_edges = IncrementalMst::from_edges(edges.clone(), 40).unwrap();
+        assert_eq!(from_edges.total_cost(), mst.total_cost());
+        assert_eq!(from_edges.edges().len(), mst.edges().len());
+        assert_eq!(IncrementalMst::new(2).unwrap().connected(0, 1), Ok(false));
+    }
+
+    #[test]
+    fn test_rollback_disjoint_set() {
+        let mut sets = RollbackDisjointSet::new(5);
+        assert_eq!(sets.len(), 5);
+        assert!(RollbackDisjointSet::new(0).is_empty());
+
+        assert!(sets.union(0, 1));
+        let snapshot = sets.snapshot();
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        // A union that changes nothing is still recorded (& undone by one rollback)
+        assert!(!sets.union(0, 2));
+        assert_eq!(sets.count(), 2);
+        assert!(sets.same_set(0, 3));
+
+        assert!(sets.rollback());
+        assert_eq!(sets.count(), 2);
+        assert!(sets.rollback());
+        assert_eq!(sets.count(), 3);
+        assert!(!sets.same_set(0, 3));
+        assert!(sets.same_set(2, 3));
+
+        sets.union(3, 4);
+        sets.rollback_to(snapshot);
+        assert_eq!(sets.count(), 4);
+        assert!(sets.same_set(0, 1));
+        assert!(!sets.same_set(2, 3));
+        assert!(!sets.same_set(3, 4));
+
+        assert!(sets.rollback());
+        assert!(!sets.rollback());
+        assert_eq!(sets.count(), 5);
+        assert!((0..5).all(|x| sets.find(x) == x));
+    }
+
+    #[test]
+    fn test_offline_connectivity() {
+        use ConnectivityEvent::*;
+        let events = vec![
+            Insert(0, 1),
+            Insert(1, 2),
+            Query(0, 2),
+            Delete(1, 0),
+            Query(0, 2),
+            Query(1, 2),
+            // Parallel edges: deleting one copy leaves the other
+            Insert(2, 3),
+            Insert(3, 2),
+            Delete(2, 3),
+            Query(1, 3),
+            Delete(3, 2),
+            Query(1, 3),
+            // Deleting a missing edge does nothing
+            Delete(0, 3),
+            Query(4, 4),
+        ];
+
+        assert_eq!(
+            offline_connectivity(&events, 5),
+            Ok(vec![true, false, true, true, false, true])
+        );
+        assert_eq!(offline_connectivity(&[], 3), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_offline_connectivity_errors() {
+        use ConnectivityEvent::*;
+
+        assert_eq!(offline_connectivity(&[], -2), Err(GraphError::NegativeVertexCount(-2)));
+        assert_eq!(
+            offline_connectivity(&[Insert(0, 1), Delete(1, 5), Query(0, 1)], 3),
+            Err(GraphError::ItemVertexOutOfRange { position: 1, vertex: 5, number_of_vertices: 3 })
+        );
+        assert_eq!(
+            offline_connectivity(&[Insert(0, 1), Query(-1, 0)], 3),
+            Err(GraphError::ItemVertexOutOfRange { position: 1, vertex: -1, number_of_vertices: 3 })
+        );
+        assert!(offline_connectivity(&[Query(0, 0)], 0).is_err());
+    }
+
+    #[test]
+    fn test_offline_connectivity_matches_rebuilding() {
+        // A random log, checked by rebuilding a DisjointSet from the present edges at every query
+        let mut random = Lcg::new(11);
+        let mut next = |n: u64| random.below(n) as i64;
+        let mut present: Vec<(i64, i64)> = Vec::new();
+        let mut events = Vec::new();
+        let mut expected = Vec::new();
+        for _ in 0..300 {
+            match next(3) {
+                0 => {
+                    let edge = (next(20), next(20));
+                    present.push(edge);
+                    events.push(ConnectivityEvent::Insert(edge.0, edge.1));
+                }
+                1 if !present.is_empty() => {
+                    let (u, v) = present.swap_remove(next(present.len() as u64) as usize);
+                    events.push(ConnectivityEvent::Delete(v, u));
+                }
+                _ => {
+                    let (u, v) = (next(20), next(20));
+                    let mut sets = DisjointSet::new(20);
+                    for (a, b) in present.iter() {
+                        sets.union(*a as usize, *b as usize);
+                    }
+                    expected.push(sets.same_set(u as usize, v as usize));
+                    events.push(ConnectivityEvent::Query(u, v));
+                }
+            }
+        }
+
+        assert_eq!(offline_connectivity(&events, 20), Ok(expected));
+    }
+
+    #[test]
+    fn test_weighted_disjoint_set() {
+        let mut sets = WeightedDisjointSet::new(5);
+        assert_eq!(sets.len(), 5);
+        assert!(WeightedDisjointSet::new(0).is_empty());
+
+        // v(0) - v(1) = 5, v(1) - v(2) = 3, v(3) - v(2) = -2
+        assert_eq!(sets.union(0, 1, 5), Ok(true));
+        assert_eq!(sets.union(1, 2, 3), Ok(true));
+        assert_eq!(sets.union(3, 2, -2), Ok(true));
+
+        assert_eq!(sets.count(), 2);
+        assert!(sets.same_set(0, 3));
+        assert_eq!(sets.difference(0, 2), Some(8));
+        assert_eq!(sets.difference(2, 0), Some(-8));
+        assert_eq!(sets.difference(0, 3), Some(10));
+        assert_eq!(sets.difference(4, 4), Some(0));
+        assert_eq!(sets.difference(0, 4), None);
+
+        // Implied by the constraints so far
+        assert_eq!(sets.union(3, 0, -10), Ok(false));
+        assert_eq!(
+            sets.union(0, 2, 7),
+            Err(Contradiction { x: 0, y: 2, known: 8, given: 7 })
+        );
+        assert_eq!(
+            Contradiction { x: 0, y: 2, known: 8, given: 7 }.to_string(),
+            "v(0) - v(2) is already known to be 8, not 7"
+        );
+        // A failed union changes nothing
+        assert_eq!(sets.count(), 2);
+        assert_eq!(sets.difference(0, 2), Some(8));
+    }
+
+    #[test]
+    fn test_weighted_disjoint_set_matches_values() {
+        // Constraints taken from hidden values are never contradictions & every difference comes out right
+        let values: Vec<i64> = (0..200).map(|i| (i * 37 % 101) - 50).collect();
+        let mut sets = WeightedDisjointSet::new(200);
+        let mut plain = DisjointSet::new(200);
+        for edge in random_edges(200, 300, 1) {
+            let (x, y) = (edge.source as usize, edge.destination as usize);
+            assert_eq!(sets.union(x, y, values[x] - values[y]), Ok(plain.union(x, y)));
+        }
+
+        assert_eq!(sets.count(), plain.count());
+        for x in 0..200 {
+            for y in [0, 17, 199] {
+                let expected = plain.same_set(x, y).then(|| values[x] - values[y]);
+                assert_eq!(sets.difference(x, y), expected);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_serde {
+    use super::*;
+
+    #[test]
+    fn test_edges_json() {
+        let edges = vec![Edge::new(0, 1, 7), Edge::new(1, 2, 5)];
+
+        let json = serde_json::to_string(&edges).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"source":0,"destination":1,"cost":7},{"source":1,"destination":2,"cost":5}]"#
+        );
+
+        let read: Vec<Edge> = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, edges);
+    }
 }
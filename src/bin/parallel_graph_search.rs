@@ -0,0 +1,333 @@
+/*
+
+How it works:
+
+Parallel Breadth First Search & Delta-Stepping Shortest Paths
+
+Only built with the `parallel` feature: `cargo test --features parallel`
+
+Both algorithms split the work of each step between threads (std::thread), then combine the results
+on one thread. Work is only split where it is independent, so the answers are exactly the same as
+the single threaded versions (Graph::shortest_path_lengths & Graph::shortest_path_costs).
+
+The graph is first copied into a compact form (nodes numbered 0..n, with a Vec of neighbours for each)
+so that threads can share it without any locking.
+
+Level-synchronous BFS:
+
+1. The frontier starts as [source] at distance 0.
+2. Split the frontier between threads. Each thread lists the neighbours of its nodes that haven't been visited.
+3. Combine the lists: every unvisited node found is at distance + 1 & makes up the next frontier.
+4. Repeat until the frontier is empty.
+
+Delta-stepping (a parallel version of Dijkstra's algorithm):
+
+Nodes are kept in buckets by tentative distance, bucket i holding distances in [i * delta, (i + 1) * delta).
+Edges with weight <= delta are "light" & heavier edges are "heavy".
+
+1. Take the first non-empty bucket.
+2. Relax the light edges of every node in it (in parallel). Relaxing can add nodes back into the same
+   bucket, so repeat until it stays empty.
+3. Relax the heavy edges of every node removed from the bucket (in parallel). These can only reach later buckets.
+4. Move to the next bucket.
+
+A small delta behaves like Dijkstra (little parallel work per step) & a large delta like Bellman-Ford
+(lots of parallel work, but nodes are relaxed many times).
+
+*/
+
+// Use the graph types from graph.rs
+#[path = "graph.rs"]
+mod graph;
+
+// Repeatable random inputs for the tests
+#[cfg(test)]
+#[path = "test_random.rs"]
+mod test_random;
+
+use graph::{Graph, GraphError};
+use std::collections::HashMap;
+use std::thread;
+
+// A graph with nodes numbered 0..n & a list of (neighbour, weight) for each node
+struct CompactGraph<'a> {
+    names: Vec<&'a String>,
+    index: HashMap<&'a String, usize>,
+    adjacency: Vec<Vec<(usize, i64)>>,
+}
+
+impl<'a> CompactGraph<'a> {
+    fn new<G: Graph>(graph: &'a G) -> CompactGraph<'a> {
+        let names: Vec<&String> = graph.nodes().into_iter().collect();
+        let index: HashMap<&String, usize> = names.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+        let adjacency = names
+            .iter()
+            .map(|n| {
+                graph.neighbours(n).unwrap().iter().map(|(m, w)| (index[m], *w as i64)).collect()
+            })
+            .collect();
+        CompactGraph {
+            names,
+            index,
+            adjacency,
+        }
+    }
+
+    fn position(&self, node: &str) -> Result<usize, GraphError> {
+        match self.index.get(&node.to_string()) {
+            None => Err(GraphError::NodeNotInGraph(node.to_string())),
+            Some(i) => Ok(*i),
+        }
+    }
+}
+
+// Run f on roughly equal chunks of items, one thread per chunk, & return the results in chunk order
+fn map_chunks<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&[T]) -> R + Sync,
+{
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = items.len().div_ceil(threads.max(1));
+    // Scoped threads may borrow items & f because they are joined before the scope ends
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items.chunks(chunk_size).map(|chunk| scope.spawn(move || f(chunk))).collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+// Number of hops from source to every node it can reach, using the given number of threads
+pub fn parallel_bfs<'a, G: Graph>(
+    graph: &'a G,
+    source: &str,
+    threads: usize,
+) -> Result<HashMap<&'a String, usize>, GraphError> {
+    let compact = CompactGraph::new(graph);
+    let source = compact.position(source)?;
+
+    let mut distances: Vec<Option<usize>> = vec![None; compact.names.len()];
+    distances[source] = Some(0);
+    let mut frontier = vec![source];
+    let mut level = 0;
+
+    while !frontier.is_empty() {
+        // 2. Each thread lists unvisited neighbours (distances is only read here)
+        let found = map_chunks(&frontier, threads, |chunk| {
+            let mut found = Vec::new();
+            for &v in chunk {
+                for &(u, _) in &compact.adjacency[v] {
+                    if distances[u].is_none() {
+                        found.push(u);
+                    }
+                }
+            }
+            found
+        });
+
+        // 3. Combine, skipping nodes found by more than one thread
+        level += 1;
+        let mut next = Vec::new();
+        for u in found.into_iter().flatten() {
+            if distances[u].is_none() {
+                distances[u] = Some(level);
+                next.push(u);
+            }
+        }
+        frontier = next;
+    }
+
+    Ok(distances
+        .into_iter()
+        .enumerate()
+        .filter_map(|(v, d)| d.map(|d| (compact.names[v], d)))
+        .collect())
+}
+
+// Cheapest total weight from source to every node it can reach, using delta-stepping
+// Edge weights must not be negative & delta must be at least 1 (GraphError::InvalidDelta otherwise)
+pub fn parallel_shortest_paths<'a, G: Graph>(
+    graph: &'a G,
+    source: &str,
+    delta: i64,
+    threads: usize,
+) -> Result<HashMap<&'a String, i64>, GraphError> {
+    if delta < 1 {
+        return Err(GraphError::InvalidDelta(delta));
+    }
+    let compact = CompactGraph::new(graph);
+    let source = compact.position(source)?;
+    for (from_node, to_node, weight) in graph.edges() {
+        if weight < 0 {
+            return Err(GraphError::NegativeWeight {
+                from: from_node.to_string(),
+                to: to_node.to_string(),
+                weight,
+            });
+        }
+    }
+
+    let mut distances: Vec<Option<i64>> = vec![None; compact.names.len()];
+    let mut buckets: Vec<Vec<usize>> = Vec::new();
+
+    // Lower the distance to v if x is shorter, moving v into the bucket for x
+    // (v is left in its old bucket & skipped there because its distance no longer matches)
+    let relax = |distances: &mut Vec<Option<i64>>, buckets: &mut Vec<Vec<usize>>, v: usize, x: i64| {
+        if distances[v].is_none_or(|d| x < d) {
+            distances[v] = Some(x);
+            let b = (x / delta) as usize;
+            if buckets.len() <= b {
+                buckets.resize(b + 1, Vec::new());
+            }
+            buckets[b].push(v);
+        }
+    };
+
+    // Each thread lists (neighbour, new distance) for the light or heavy edges of its nodes
+    let requests = |nodes: &[usize], distances: &[Option<i64>], light: bool| -> Vec<(usize, i64)> {
+        map_chunks(nodes, threads, |chunk| {
+            let mut found = Vec::new();
+            for &v in chunk {
+                let d = distances[v].unwrap();
+                for &(u, w) in &compact.adjacency[v] {
+                    if (w <= delta) == light {
+                        found.push((u, d + w));
+                    }
+                }
+            }
+            found
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    };
+
+    relax(&mut distances, &mut buckets, source, 0);
+
+    let mut i = 0;
+    while i < buckets.len() {
+        // Nodes settled in this bucket (for the heavy edges at the end)
+        let mut settled: Vec<usize> = Vec::new();
+
+        // 2. Light edges, until the bucket stays empty
+        while !buckets[i].is_empty() {
+            let mut current: Vec<usize> = std::mem::take(&mut buckets[i])
+                .into_iter()
+                .filter(|&v| (distances[v].unwrap() / delta) as usize == i)
+                .collect();
+            current.sort_unstable();
+            current.dedup();
+
+            for (u, x) in requests(&current, &distances, true) {
+                relax(&mut distances, &mut buckets, u, x);
+            }
+            settled.extend(current);
+        }
+
+        // 3. Heavy edges
+        settled.sort_unstable();
+        settled.dedup();
+        for (u, x) in requests(&settled, &distances, false) {
+            relax(&mut distances, &mut buckets, u, x);
+        }
+
+        i += 1;
+    }
+
+    Ok(distances
+        .into_iter()
+        .enumerate()
+        .filter_map(|(v, d)| d.map(|d| (compact.names[v], d)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::{DirectedGraph, UndirectedGraph};
+    use test_random::Lcg;
+
+    // A pseudo-random graph (see test_random.rs) so tests are repeatable
+    fn random_graph<G: Graph>(mut graph: G, nodes: u64, edges: u64, max_weight: u64) -> G {
+        let mut random = Lcg::new(42);
+        let mut next = |n: u64| random.below(n);
+        for i in 0..nodes {
+            graph.add_node(&i.to_string());
+        }
+        for _ in 0..edges {
+            let (a, b, w) = (next(nodes), next(nodes), next(max_weight + 1));
+            graph.add_edge((&a.to_string(), &b.to_string(), w as i32));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_parallel_bfs() {
+        let directed = random_graph(DirectedGraph::new(), 300, 900, 20);
+        let undirected = random_graph(UndirectedGraph::new(), 300, 400, 20);
+
+        for threads in 1..=4 {
+            assert_eq!(
+                parallel_bfs(&directed, "0", threads).unwrap(),
+                directed.shortest_path_lengths("0").unwrap()
+            );
+            assert_eq!(
+                parallel_bfs(&undirected, "7", threads).unwrap(),
+                undirected.shortest_path_lengths("7").unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_parallel_shortest_paths() {
+        let directed = random_graph(DirectedGraph::new(), 300, 900, 20);
+        let undirected = random_graph(UndirectedGraph::new(), 300, 400, 20);
+
+        for threads in 1..=4 {
+            for delta in [1, 5, 20, 1000].iter() {
+                assert_eq!(
+                    parallel_shortest_paths(&directed, "0", *delta, threads).unwrap(),
+                    directed.shortest_path_costs("0").unwrap()
+                );
+                assert_eq!(
+                    parallel_shortest_paths(&undirected, "7", *delta, threads).unwrap(),
+                    undirected.shortest_path_costs("7").unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_graph() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 10));
+        graph.add_edge(("a", "c", 20));
+        graph.add_node("d");
+
+        let distances = parallel_bfs(&graph, "a", 2).unwrap();
+        assert_eq!(distances.len(), 3);
+        assert_eq!(distances[&String::from("c")], 1);
+
+        let costs = parallel_shortest_paths(&graph, "a", 3, 2).unwrap();
+        assert_eq!(costs.len(), 3);
+        assert_eq!(costs[&String::from("c")], 15);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", -5));
+
+        assert_eq!(parallel_bfs(&graph, "z", 2), Err(GraphError::NodeNotInGraph(String::from("z"))));
+        assert_eq!(
+            parallel_shortest_paths(&graph, "a", 3, 2),
+            Err(GraphError::NegativeWeight { from: String::from("a"), to: String::from("b"), weight: -5 })
+        );
+        assert_eq!(parallel_shortest_paths(&graph, "a", 0, 2), Err(GraphError::InvalidDelta(0)));
+        assert_eq!(parallel_shortest_paths(&graph, "a", -3, 2), Err(GraphError::InvalidDelta(-3)));
+    }
+}
@@ -0,0 +1,401 @@
+/*
+
+How it works:
+
+Sort Visualiser
+
+Replays a sort one step at a time, drawing the array as a bar chart after every comparison & swap.
+
+1. Sort a copy of the numbers with a trace (see sort_instrumentation.rs), which lists every
+   Compare(i, j), Swap(i, j) & Pass in order.
+2. Start from the original numbers & replay the trace: a swap changes the bars, a comparison only
+   highlights them. Each event gives one frame.
+3. Draw each frame as text (for the terminal) or as an SVG image.
+
+cargo run --bin sort_visualiser -- bubble 5 1 4 2 3               (every frame, one after another)
+cargo run --bin sort_visualiser -- heap --animate 200 5 1 4 2 3   (redraw in place, 200ms per frame)
+cargo run --bin sort_visualiser -- comb --svg frames 5 1 4 2 3    (frames/frame_0000.svg, frame_0001.svg, ...)
+
+The SVG frames can be joined into a GIF with another tool, e.g. ImageMagick: `convert -delay 20 frames/frame_*.svg sort.gif`
+
+Compared to each other, the frames show what the "How it works" comment of each sort describes:
+
+bubble:          the largest value bubbles up to the end on every pass
+cocktail_shaker: passes alternate between bubbling up & sinking down
+comb:            early passes compare & swap values far apart (the gap), later passes are bubble sort
+heap:            first the array is arranged into a heap, then the root is swapped to the end each pass
+
+In a text frame, compared bars are drawn with "?" & swapped bars with "*":
+
+compare 0 & 1 (comparisons: 1, swaps: 0, passes: 1)
+?
+?     #
+?  ?  #
+3  1  2
+
+*/
+
+// Use the sorts (& their instrumentation) from sorter.rs
+#[path = "sorter.rs"]
+mod sorter;
+
+use rust_dsa::sort_instrumentation::{Instrumentation, SortEvent};
+use sorter::Sorter;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+// Bars taller than this are scaled down to fit the terminal
+const MAX_ROWS: u32 = 20;
+
+// Width of a bar in an SVG frame & the height of the tallest bar
+// (one pixel per value, bars taller than MAX_BAR_HEIGHT are scaled down to fit)
+// Pixel sizes are u64 so that long inputs can't overflow the image width
+const BAR_WIDTH: u64 = 20;
+const MAX_BAR_HEIGHT: u32 = 400;
+const SVG_CAPTION_HEIGHT: u64 = 30;
+
+// The array at one step of the sort
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub values: Vec<u32>,
+    // The event that led to this frame (None for the first & last frames)
+    pub event: Option<SortEvent>,
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub passes: usize,
+}
+
+impl Frame {
+    // What happened in this frame, e.g. "swap 2 & 3"
+    pub fn caption(&self) -> String {
+        let step = match self.event {
+            None if self.comparisons == 0 && self.swaps == 0 => String::from("start"),
+            None => String::from("sorted"),
+            Some(SortEvent::Compare(i, j)) => format!("compare {} & {}", i, j),
+            Some(SortEvent::Swap(i, j)) => format!("swap {} & {}", i, j),
+            Some(SortEvent::Pass) => format!("pass {}", self.passes),
+        };
+        format!(
+            "{} (comparisons: {}, swaps: {}, passes: {})",
+            step, self.comparisons, self.swaps, self.passes
+        )
+    }
+
+    // Mark for the bar at position i: '?' if compared, '*' if swapped, '#' otherwise
+    fn mark(&self, i: usize) -> char {
+        match self.event {
+            Some(SortEvent::Compare(a, b)) if i == a || i == b => '?',
+            Some(SortEvent::Swap(a, b)) if i == a || i == b => '*',
+            _ => '#',
+        }
+    }
+}
+
+// Every frame of sorting values: the start, one per event & the sorted result
+pub fn frames(sorter: Sorter, values: &[u32]) -> Vec<Frame> {
+    let mut instrumentation = Instrumentation::with_trace();
+    sorter.sort_instrumented(&mut values.to_vec(), &mut instrumentation);
+
+    let mut frame = Frame {
+        values: values.to_vec(),
+        event: None,
+        comparisons: 0,
+        swaps: 0,
+        passes: 0,
+    };
+    let mut frames = vec![frame.clone()];
+    for event in instrumentation.trace().unwrap() {
+        match *event {
+            SortEvent::Compare(_, _) => frame.comparisons += 1,
+            SortEvent::Swap(i, j) => {
+                frame.values.swap(i, j);
+                frame.swaps += 1;
+            }
+            SortEvent::Pass => frame.passes += 1,
+        }
+        frame.event = Some(*event);
+        frames.push(frame.clone());
+    }
+    frame.event = None;
+    frames.push(frame);
+    frames
+}
+
+// Height of each bar (one unit per value) & of the tallest bar, scaled down if that is above limit
+// Heights are rounded up, so only 0 has no bar
+fn bar_heights(values: &[u32], limit: u32) -> (u32, Vec<u32>) {
+    let max = values.iter().copied().max().unwrap_or(0);
+    let tallest = max.min(limit);
+    let heights = values
+        .iter()
+        .map(|v| if max == 0 { 0 } else { ((*v as u64 * tallest as u64).div_ceil(max as u64)) as u32 })
+        .collect();
+    (tallest, heights)
+}
+
+// A frame as a text bar chart, with the caption above & the values below
+pub fn render_ascii(frame: &Frame) -> String {
+    let max = frame.values.iter().copied().max().unwrap_or(0);
+    let (rows, heights) = bar_heights(&frame.values, MAX_ROWS);
+    let width = max.to_string().len() + 2;
+
+    let mut lines = vec![frame.caption()];
+    for row in (1..=rows).rev() {
+        let line: String = heights
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                let mark = if *h >= row { frame.mark(i) } else { ' ' };
+                format!("{:<width$}", mark, width = width)
+            })
+            .collect();
+        lines.push(line.trim_end().to_string());
+    }
+    let labels: String = frame.values.iter().map(|v| format!("{:<width$}", v, width = width)).collect();
+    lines.push(labels.trim_end().to_string());
+    lines.join("\n") + "\n"
+}
+
+// A frame as an SVG image: compared bars are orange, swapped bars red & the rest grey
+pub fn render_svg(frame: &Frame) -> String {
+    let (tallest, heights) = bar_heights(&frame.values, MAX_BAR_HEIGHT);
+    let width = (frame.values.len() as u64 * BAR_WIDTH).max(200);
+    let height = tallest.max(1) as u64 + SVG_CAPTION_HEIGHT;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n",
+        width, height, width, height
+    );
+    svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"white\"/>\n", width, height));
+    svg.push_str(&format!(
+        "<text x=\"4\" y=\"20\" font-family=\"monospace\" font-size=\"12\">{}</text>\n",
+        frame.caption().replace('&', "&amp;")
+    ));
+    for (i, h) in heights.iter().enumerate() {
+        let colour = match frame.mark(i) {
+            '?' => "orange",
+            '*' => "red",
+            _ => "grey",
+        };
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            i as u64 * BAR_WIDTH + 1,
+            height - *h as u64,
+            BAR_WIDTH - 2,
+            h,
+            colour
+        ));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// How to show the frames
+#[derive(Debug, Clone, PartialEq)]
+pub enum Mode {
+    // Print every frame, one after another
+    Steps,
+    // Redraw each frame in place, waiting this many milliseconds between frames
+    Animate(u64),
+    // Write each frame to an SVG file in this directory
+    Svg(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub sorter: Sorter,
+    pub mode: Mode,
+    pub values: Vec<u32>,
+}
+
+const USAGE: &str = "usage: sort_visualiser <bubble|cocktail_shaker|comb|heap> [--animate <ms> | --svg <directory>] [numbers...]";
+
+// Numbers sorted when none are given
+const EXAMPLE: [u32; 10] = [7, 3, 9, 1, 6, 10, 2, 8, 5, 4];
+
+// Parse the command line arguments (without the program name)
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let sorter: Sorter = match args.first() {
+        None => return Err(String::from(USAGE)),
+        Some(name) => name.parse().map_err(|e| format!("{}\n{}", e, USAGE))?,
+    };
+
+    let mut mode = Mode::Steps;
+    let mut values = Vec::new();
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--animate" => {
+                let delay = rest.next().ok_or_else(|| String::from(USAGE))?;
+                let delay = delay.parse().map_err(|_| format!("invalid delay {}", delay))?;
+                mode = Mode::Animate(delay);
+            }
+            "--svg" => {
+                let directory = rest.next().ok_or_else(|| String::from(USAGE))?;
+                mode = Mode::Svg(PathBuf::from(directory));
+            }
+            number => values.push(number.parse().map_err(|_| format!("invalid number {}", number))?),
+        }
+    }
+    if values.is_empty() {
+        values = EXAMPLE.to_vec();
+    }
+    Ok(Options { sorter, mode, values })
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = parse_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+
+    let frames = frames(options.sorter, &options.values);
+    match options.mode {
+        Mode::Steps => {
+            for frame in frames.iter() {
+                println!("{}", render_ascii(frame));
+            }
+        }
+        Mode::Animate(delay) => {
+            for frame in frames.iter() {
+                // Clear the terminal & move the cursor to the top left
+                print!("\x1b[2J\x1b[H{}", render_ascii(frame));
+                thread::sleep(Duration::from_millis(delay));
+            }
+        }
+        Mode::Svg(directory) => {
+            let written = fs::create_dir_all(&directory).and_then(|_| {
+                for (i, frame) in frames.iter().enumerate() {
+                    fs::write(directory.join(format!("frame_{:04}.svg", i)), render_svg(frame))?;
+                }
+                Ok(())
+            });
+            if let Err(e) = written {
+                eprintln!("could not write frames to {}: {}", directory.display(), e);
+                process::exit(1);
+            }
+            println!("wrote {} frames to {}", frames.len(), directory.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_sort_visualiser {
+    use super::*;
+
+    #[test]
+    fn test_frames() {
+        for sorter in Sorter::ALL.iter() {
+            let frames = frames(*sorter, &EXAMPLE);
+            let first = frames.first().unwrap();
+            let last = frames.last().unwrap();
+
+            assert_eq!(first.values, EXAMPLE.to_vec());
+            assert_eq!(last.values, (1..=10).collect::<Vec<u32>>(), "{}", sorter);
+            assert_eq!((first.event, last.event), (None, None));
+
+            // The last frame has the same counts as sorting with instrumentation
+            let mut instrumentation = Instrumentation::new();
+            sorter.sort_instrumented(&mut EXAMPLE.to_vec(), &mut instrumentation);
+            assert_eq!(
+                (last.comparisons, last.swaps, last.passes),
+                (instrumentation.comparisons, instrumentation.swaps, instrumentation.passes)
+            );
+            assert_eq!(frames.len(), last.comparisons + last.swaps + last.passes + 2);
+        }
+    }
+
+    #[test]
+    fn test_render_ascii() {
+        let frames = frames(Sorter::Bubble, &[3, 1, 2]);
+
+        assert_eq!(render_ascii(&frames[0]), "start (comparisons: 0, swaps: 0, passes: 0)\n#\n#     #\n#  #  #\n3  1  2\n");
+        assert_eq!(
+            render_ascii(&frames[2]),
+            "compare 0 & 1 (comparisons: 1, swaps: 0, passes: 1)\n?\n?     #\n?  ?  #\n3  1  2\n"
+        );
+        assert_eq!(
+            render_ascii(&frames[3]),
+            "swap 0 & 1 (comparisons: 1, swaps: 1, passes: 1)\n   *\n   *  #\n*  *  #\n1  3  2\n"
+        );
+        assert_eq!(
+            render_ascii(frames.last().unwrap()),
+            "sorted (comparisons: 3, swaps: 2, passes: 3)\n      #\n   #  #\n#  #  #\n1  2  3\n"
+        );
+    }
+
+    #[test]
+    fn test_render_ascii_scaled() {
+        let frame = &frames(Sorter::Heap, &[100, 0, 50])[0];
+        let text = render_ascii(frame);
+        let lines: Vec<&str> = text.lines().collect();
+
+        // Caption, MAX_ROWS rows of bars & the values
+        assert_eq!(lines.len(), MAX_ROWS as usize + 2);
+        assert_eq!(lines[1], "#");
+        assert_eq!(lines[MAX_ROWS as usize], "#         #");
+        assert_eq!(lines.last(), Some(&"100  0    50"));
+        assert_eq!(render_ascii(&frames(Sorter::Comb, &[])[0]), "start (comparisons: 0, swaps: 0, passes: 0)\n\n");
+    }
+
+    #[test]
+    fn test_render_svg() {
+        let frames = frames(Sorter::CocktailShaker, &[3, 1, 2]);
+        let svg = render_svg(&frames[3]);
+
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"33\""));
+        assert!(svg.contains(">swap 0 &amp; 1 (comparisons: 1, swaps: 1, passes: 1)</text>"));
+        assert!(svg.contains("<rect x=\"1\" y=\"32\" width=\"18\" height=\"1\" fill=\"red\"/>"));
+        assert!(svg.contains("<rect x=\"21\" y=\"30\" width=\"18\" height=\"3\" fill=\"red\"/>"));
+        assert!(svg.contains("<rect x=\"41\" y=\"31\" width=\"18\" height=\"2\" fill=\"grey\"/>"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_render_svg_scaled() {
+        let frame = &frames(Sorter::Bubble, &[1000000, 0, 1])[0];
+        let svg = render_svg(frame);
+
+        // The tallest bar is MAX_BAR_HEIGHT pixels & small values still get a bar
+        let height = MAX_BAR_HEIGHT as u64 + SVG_CAPTION_HEIGHT;
+        assert!(svg.starts_with(&format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"200\" height=\"{}\"",
+            height
+        )));
+        assert!(svg.contains(&format!("<rect x=\"1\" y=\"30\" width=\"18\" height=\"{}\"", MAX_BAR_HEIGHT)));
+        assert!(svg.contains(&format!("<rect x=\"21\" y=\"{}\" width=\"18\" height=\"0\"", height)));
+        assert!(svg.contains(&format!("<rect x=\"41\" y=\"{}\" width=\"18\" height=\"1\"", height - 1)));
+
+        let svg = render_svg(&frames(Sorter::Bubble, &[0, 0])[0]);
+        assert!(svg.contains("height=\"31\""));
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let args = |s: &str| -> Vec<String> { s.split_whitespace().map(String::from).collect() };
+
+        assert_eq!(
+            parse_args(&args("bubble 3 1 2")),
+            Ok(Options { sorter: Sorter::Bubble, mode: Mode::Steps, values: vec![3, 1, 2] })
+        );
+        assert_eq!(
+            parse_args(&args("heap_sort --animate 50 2 1")),
+            Ok(Options { sorter: Sorter::Heap, mode: Mode::Animate(50), values: vec![2, 1] })
+        );
+        assert_eq!(
+            parse_args(&args("Cocktail-Shaker --svg frames")),
+            Ok(Options { sorter: Sorter::CocktailShaker, mode: Mode::Svg(PathBuf::from("frames")), values: EXAMPLE.to_vec() })
+        );
+
+        assert_eq!(parse_args(&[]), Err(String::from(USAGE)));
+        assert!(parse_args(&args("quick 1 2")).unwrap_err().starts_with("unknown sorter quick"));
+        assert_eq!(parse_args(&args("comb 1 -2")), Err(String::from("invalid number -2")));
+        assert_eq!(parse_args(&args("comb --animate")), Err(String::from(USAGE)));
+        assert_eq!(parse_args(&args("comb --animate soon")), Err(String::from("invalid delay soon")));
+    }
+}
@@ -0,0 +1,316 @@
+/*
+
+How it works:
+
+Sorter
+
+One type for choosing a sorting algorithm at run time (e.g. from a configuration file), with the same
+sort / sort_by / sort_by_key methods as slices in the standard library:
+
+let sorter: Sorter = "heap".parse().unwrap();
+sorter.sort(&mut numbers);
+sorter.sort_by(&mut numbers, |a, b| b.cmp(a));
+sorter.sort_by_key(&mut people, |person| person.age);
+
+Each variant calls the *_sort_by / *_sort_by_key functions from its own file.
+sort is sort_by with the comparison from Ord.
+
+sort_instrumented counts (& can trace) what the sort does, see sort_instrumentation.rs:
+
+let mut instrumentation = Instrumentation::new();
+Sorter::Bubble.sort_instrumented(&mut numbers, &mut instrumentation);
+println!("{} comparisons", instrumentation.comparisons);
+
+*/
+
+// Use the sorts from their own files
+#[path = "bubble_sort.rs"]
+mod bubble_sort;
+#[path = "cocktail_shaker_sort.rs"]
+mod cocktail_shaker_sort;
+#[path = "comb_sort.rs"]
+mod comb_sort;
+#[path = "heap_sort.rs"]
+mod heap_sort;
+
+// Every sort uses the same instrumentation types (from the library, see src/sort_instrumentation.rs)
+use rust_dsa::sort_instrumentation::Instrumentation;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sorter {
+    Bubble,
+    CocktailShaker,
+    Comb,
+    Heap,
+}
+
+impl Sorter {
+    // Every algorithm, e.g. to compare them on the same input
+    pub const ALL: [Sorter; 4] = [Sorter::Bubble, Sorter::CocktailShaker, Sorter::Comb, Sorter::Heap];
+
+    pub fn sort<T: Ord>(&self, arr: &mut [T]) {
+        self.sort_by(arr, |a, b| a.cmp(b))
+    }
+
+    pub fn sort_by<T, F: FnMut(&T, &T) -> Ordering>(&self, arr: &mut [T], compare: F) {
+        match self {
+            Sorter::Bubble => bubble_sort::bubble_sort_by(arr, compare),
+            Sorter::CocktailShaker => cocktail_shaker_sort::cocktail_shaker_sort_by(arr, compare),
+            Sorter::Comb => comb_sort::comb_sort_by(arr, compare),
+            Sorter::Heap => heap_sort::heap_sort_by(arr, compare),
+        }
+    }
+
+    // Sort by a key taken from each element (the key is recalculated for every comparison)
+    pub fn sort_by_key<T, K: Ord, F: FnMut(&T) -> K>(&self, arr: &mut [T], key: F) {
+        match self {
+            Sorter::Bubble => bubble_sort::bubble_sort_by_key(arr, key),
+            Sorter::CocktailShaker => cocktail_shaker_sort::cocktail_shaker_sort_by_key(arr, key),
+            Sorter::Comb => comb_sort::comb_sort_by_key(arr, key),
+            Sorter::Heap => heap_sort::heap_sort_by_key(arr, key),
+        }
+    }
+
+    // Sort, counting (& optionally tracing) comparisons, swaps & passes
+    pub fn sort_instrumented<T: Ord>(&self, arr: &mut [T], instrumentation: &mut Instrumentation) {
+        self.sort_instrumented_by(arr, |a, b| a.cmp(b), instrumentation)
+    }
+
+    pub fn sort_instrumented_by<T, F: FnMut(&T, &T) -> Ordering>(
+        &self,
+        arr: &mut [T],
+        compare: F,
+        instrumentation: &mut Instrumentation,
+    ) {
+        match self {
+            Sorter::Bubble => bubble_sort::bubble_sort_instrumented_by(arr, compare, instrumentation),
+            Sorter::CocktailShaker => {
+                cocktail_shaker_sort::cocktail_shaker_sort_instrumented_by(arr, compare, instrumentation)
+            }
+            Sorter::Comb => comb_sort::comb_sort_instrumented_by(arr, compare, instrumentation),
+            Sorter::Heap => heap_sort::heap_sort_instrumented_by(arr, compare, instrumentation),
+        }
+    }
+
+    // Name used by Display & FromStr
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sorter::Bubble => "bubble",
+            Sorter::CocktailShaker => "cocktail_shaker",
+            Sorter::Comb => "comb",
+            Sorter::Heap => "heap",
+        }
+    }
+}
+
+impl fmt::Display for Sorter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// A name that isn't one of the sorters (kept for the error message)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseSorterError(String);
+
+impl fmt::Display for ParseSorterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown sorter {} (expected bubble, cocktail_shaker, comb or heap)",
+            self.0
+        )
+    }
+}
+
+impl Error for ParseSorterError {}
+
+// Parse a name like "heap" or "Cocktail-Shaker" (case, "-" & "_" are ignored & "_sort" may be added)
+impl FromStr for Sorter {
+    type Err = ParseSorterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalised = s.trim().to_lowercase().replace('-', "_");
+        let name = normalised.strip_suffix("_sort").unwrap_or(&normalised);
+        Sorter::ALL
+            .iter()
+            .find(|sorter| sorter.name().replace('_', "") == name.replace('_', ""))
+            .copied()
+            .ok_or_else(|| ParseSorterError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_dsa::sort_instrumentation::SortEvent;
+
+    fn examples() -> Vec<Vec<i32>> {
+        vec![
+            vec![],
+            vec![1],
+            vec![6, 5, 4, 3, 2, 1],
+            vec![1, 2, 3, 4, 5, 6],
+            vec![4, 5, 1, 2, 6, 7, 5, 1],
+            (0..100).map(|i| (i * 37) % 23 - 11).collect(),
+        ]
+    }
+
+    #[test]
+    fn test_sort() {
+        for sorter in Sorter::ALL.iter() {
+            for example in examples() {
+                let mut expected = example.clone();
+                expected.sort();
+
+                let mut arr = example.clone();
+                sorter.sort(&mut arr);
+                assert_eq!(arr, expected, "{}", sorter);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_by() {
+        for sorter in Sorter::ALL.iter() {
+            for example in examples() {
+                let mut expected = example.clone();
+                expected.sort_by(|a, b| b.cmp(a));
+
+                let mut arr = example.clone();
+                sorter.sort_by(&mut arr, |a, b| b.cmp(a));
+                assert_eq!(arr, expected, "{}", sorter);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_by_moves_elements() {
+        // Strings aren't Copy, so every element has to be moved (swapped) into its place
+        let words: Vec<String> = (0..50).map(|i| format!("{:02}", (i * 17) % 50)).collect();
+        for sorter in Sorter::ALL.iter() {
+            let mut expected = words.clone();
+            expected.sort_by(|a, b| b.cmp(a));
+
+            let mut arr = words.clone();
+            sorter.sort_by(&mut arr, |a, b| b.cmp(a));
+            assert_eq!(arr, expected, "{}", sorter);
+        }
+    }
+
+    #[test]
+    fn test_sort_by_key() {
+        for sorter in Sorter::ALL.iter() {
+            let mut words = vec!["pear", "fig", "banana", "kiwi", "apple"];
+            sorter.sort_by_key(&mut words, |word| word.len());
+            let lengths: Vec<usize> = words.iter().map(|word| word.len()).collect();
+            assert_eq!(lengths, vec![3, 4, 4, 5, 6], "{}", sorter);
+        }
+    }
+
+    #[test]
+    fn test_sort_by_other_types() {
+        struct Person {
+            name: &'static str,
+            age: u32,
+        }
+
+        // Floats aren't Ord, total_cmp puts -0.0 before 0.0
+        let floats: Vec<f64> = vec![2.5, -1.0, 0.0, -0.0, 10.25, 3.0];
+        // (descending, expected)
+        let float_cases: [(bool, Vec<f64>); 2] = [
+            (false, vec![-1.0, -0.0, 0.0, 2.5, 3.0, 10.25]),
+            (true, vec![10.25, 3.0, 2.5, 0.0, -0.0, -1.0]),
+        ];
+
+        for sorter in Sorter::ALL.iter() {
+            for (descending, expected) in float_cases.iter() {
+                let mut arr = floats.clone();
+                sorter.sort_by(&mut arr, |a: &f64, b| if *descending { b.total_cmp(a) } else { a.total_cmp(b) });
+                let signs: Vec<bool> = arr.iter().map(|x| x.is_sign_negative()).collect();
+                let expected_signs: Vec<bool> = expected.iter().map(|x| x.is_sign_negative()).collect();
+                assert_eq!((arr, signs), (expected.clone(), expected_signs), "{}", sorter);
+            }
+
+            let mut people = vec![
+                Person { name: "a", age: 40 },
+                Person { name: "b", age: 25 },
+                Person { name: "c", age: 31 },
+            ];
+            sorter.sort_by_key(&mut people, |person| person.age);
+            let names: Vec<&str> = people.iter().map(|person| person.name).collect();
+            assert_eq!(names, vec!["b", "c", "a"], "{}", sorter);
+
+            sorter.sort_by_key(&mut people, |person| std::cmp::Reverse(person.age));
+            let names: Vec<&str> = people.iter().map(|person| person.name).collect();
+            assert_eq!(names, vec!["a", "c", "b"], "{}", sorter);
+        }
+    }
+
+    #[test]
+    fn test_sort_instrumented() {
+        for sorter in Sorter::ALL.iter() {
+            for example in examples() {
+                let mut expected = example.clone();
+                expected.sort();
+
+                let mut arr = example.clone();
+                let mut instrumentation = Instrumentation::with_trace();
+                sorter.sort_instrumented(&mut arr, &mut instrumentation);
+                assert_eq!(arr, expected, "{}", sorter);
+
+                let mut replay = example.clone();
+                for event in instrumentation.trace().unwrap() {
+                    if let SortEvent::Swap(i, j) = event {
+                        replay.swap(*i, *j);
+                    }
+                }
+                assert_eq!(replay, expected, "{}", sorter);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pathological_input() {
+        // Sorted input is the best case for bubble sort's swaps & reversed input the worst
+        let sorted: Vec<i32> = (0..50).collect();
+        let reversed: Vec<i32> = (0..50).rev().collect();
+
+        let mut best = Instrumentation::new();
+        Sorter::Bubble.sort_instrumented(&mut sorted.clone(), &mut best);
+        let mut worst = Instrumentation::new();
+        Sorter::Bubble.sort_instrumented(&mut reversed.clone(), &mut worst);
+
+        assert_eq!(best.swaps, 0);
+        assert_eq!(worst.swaps, 50 * 49 / 2);
+        assert_eq!(best.comparisons, worst.comparisons);
+
+        // Cocktail shaker sort stops after one pass on sorted input
+        let mut instrumentation = Instrumentation::new();
+        Sorter::CocktailShaker.sort_instrumented_by(&mut sorted.clone(), |a, b| a.cmp(b), &mut instrumentation);
+        assert_eq!((instrumentation.comparisons, instrumentation.passes), (49, 1));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("bubble".parse(), Ok(Sorter::Bubble));
+        assert_eq!("Cocktail-Shaker".parse(), Ok(Sorter::CocktailShaker));
+        assert_eq!("cocktail_shaker_sort".parse(), Ok(Sorter::CocktailShaker));
+        assert_eq!(" COMB ".parse(), Ok(Sorter::Comb));
+        assert_eq!("heap_sort".parse(), Ok(Sorter::Heap));
+        for sorter in Sorter::ALL.iter() {
+            assert_eq!(sorter.to_string().parse(), Ok(*sorter));
+        }
+
+        let error = "quick".parse::<Sorter>().unwrap_err();
+        assert_eq!(error, ParseSorterError(String::from("quick")));
+        assert_eq!(
+            error.to_string(),
+            "unknown sorter quick (expected bubble, cocktail_shaker, comb or heap)"
+        );
+    }
+}
@@ -0,0 +1,261 @@
+/*
+
+How it works:
+
+Steiner Tree (2-approximation)
+
+A Steiner tree connects a chosen subset of the nodes (the "terminals") as cheaply as possible.
+Unlike a spanning tree it doesn't need to reach every node, but it may pass through other nodes
+(Steiner nodes) when that is cheaper. Finding the cheapest one is NP-hard, so this finds a tree
+costing at most twice the cheapest (Kou, Markowsky & Berman):
+
+1. Metric closure: find the shortest path between every pair of terminals (Dijkstra from each terminal).
+2. Find the minimum spanning tree of the terminals, where each pair is joined by the cost of its shortest path (Kruskal).
+3. Replace each edge of that tree by the shortest path it stands for. The paths can share edges.
+4. Find the minimum spanning tree of the resulting subgraph (removing any cycles made by overlapping paths).
+5. Prune: repeatedly remove leaves that aren't terminals (they connect nothing that's needed).
+
+Consider the graph, with terminals a, b & c:
+
+(a, b, 5) (b, c, 5) (a, c, 5)
+(a, s, 3) (b, s, 3) (c, s, 3)
+
+The cheapest Steiner tree goes through s, cost = 9
+Each pair of terminals is 5 apart, so this finds a tree of cost 10 (e.g. (a, b) (b, c))
+
+*/
+
+// Use Kruskal's algorithm from minimum_spanning_tree.rs (& the graph types from graph.rs, which it includes)
+#[path = "minimum_spanning_tree.rs"]
+mod minimum_spanning_tree;
+
+use minimum_spanning_tree::graph::{EdgePolicy, Graph, GraphError, UndirectedGraph};
+use minimum_spanning_tree::{minimum_spanning_tree, try_kruskal, Edge};
+use std::collections::{HashMap, HashSet};
+
+// Steiner tree connecting terminals (total cost & tree), costing at most twice the cheapest
+// Edge weights must not be negative & every terminal must be reachable from the others
+pub fn steiner_tree(graph: &UndirectedGraph, terminals: &[&str]) -> Result<(i64, UndirectedGraph), GraphError> {
+    // Terminals as the graph's own Strings (ignoring repeats)
+    let mut names: Vec<&String> = Vec::new();
+    for terminal in terminals {
+        match graph.adjacency_table().get_key_value(*terminal) {
+            None => return Err(GraphError::NodeNotInGraph(terminal.to_string())),
+            Some((name, _)) if !names.contains(&name) => names.push(name),
+            Some(_) => (),
+        }
+    }
+
+    // 1. Metric closure over the terminals (Dijkstra's algorithm from each, which also checks for negative weights)
+    let searches = names
+        .iter()
+        .map(|name| graph.shortest_path_tree(name))
+        .collect::<Result<Vec<_>, GraphError>>()?;
+    let mut closure: Vec<Edge> = Vec::new();
+    for (i, search) in searches.iter().enumerate() {
+        for (j, name) in names.iter().enumerate().skip(i + 1) {
+            match search.get(name) {
+                None => return Err(GraphError::Disconnected),
+                Some((cost, _)) => closure.push(Edge::new(i as i64, j as i64, *cost)),
+            }
+        }
+    }
+
+    // 2. Minimum spanning tree of the closure
+    let (_, closure_tree) = try_kruskal(closure, names.len() as i64)?;
+
+    // 3. Expand each closure edge into its shortest path (parallel edges keep the cheapest weight)
+    let mut subgraph = UndirectedGraph::with_policy(EdgePolicy::KeepMinWeight);
+    for name in names.iter() {
+        subgraph.add_node(name);
+    }
+    for edge in closure_tree.iter() {
+        let search = &searches[edge.source as usize];
+        let mut node = names[edge.destination as usize];
+        while let Some((previous, weight)) = search[node].1 {
+            subgraph.add_edge((previous, node, weight));
+            node = previous;
+        }
+    }
+
+    // 4. Minimum spanning tree of the paths (an empty list of terminals gives an empty tree)
+    let tree = match minimum_spanning_tree(&subgraph) {
+        Err(GraphError::EmptyGraph) => return Ok((0, UndirectedGraph::new())),
+        result => result?.1,
+    };
+
+    // 5. Prune leaves that aren't terminals
+    let terminal_set: HashSet<&String> = names.iter().cloned().collect();
+    let mut removed: HashSet<&String> = HashSet::new();
+    let mut degree: HashMap<&String, usize> = tree
+        .nodes()
+        .into_iter()
+        .map(|node| (node, tree.neighbours(node).unwrap().len()))
+        .collect();
+    let mut leaves: Vec<&String> = degree
+        .iter()
+        .filter(|(node, d)| **d == 1 && !terminal_set.contains(*node))
+        .map(|(node, _)| *node)
+        .collect();
+    while let Some(leaf) = leaves.pop() {
+        removed.insert(leaf);
+        for (neighbour, _) in tree.neighbours(leaf).unwrap() {
+            if removed.contains(neighbour) {
+                continue;
+            }
+            let d = degree.get_mut(neighbour).unwrap();
+            *d -= 1;
+            if *d == 1 && !terminal_set.contains(neighbour) {
+                leaves.push(neighbour);
+            }
+        }
+    }
+
+    let mut steiner = UndirectedGraph::new();
+    let mut total_cost: i64 = 0;
+    for node in tree.nodes().into_iter().filter(|n| !removed.contains(n)) {
+        steiner.add_node(node);
+    }
+    for (from_node, to_node, weight) in tree.edges() {
+        if from_node < to_node && !removed.contains(from_node) && !removed.contains(to_node) {
+            steiner.add_edge((from_node, to_node, weight));
+            total_cost += weight as i64;
+        }
+    }
+    Ok((total_cost, steiner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The example from the top of the file
+    fn triangle_with_centre() -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("b", "c", 5));
+        graph.add_edge(("a", "c", 5));
+        graph.add_edge(("a", "s", 3));
+        graph.add_edge(("b", "s", 3));
+        graph.add_edge(("c", "s", 3));
+        graph
+    }
+
+    // A 4 x 4 grid of nodes "row,column" with pseudo-random weights
+    fn grid_graph() -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        let mut weight = 3;
+        for row in 0..4 {
+            for column in 0..4 {
+                let node = format!("{},{}", row, column);
+                weight = (weight * 7 + 5) % 11 + 1;
+                if row < 3 {
+                    graph.add_edge((&node, &format!("{},{}", row + 1, column), weight));
+                }
+                weight = (weight * 7 + 5) % 11 + 1;
+                if column < 3 {
+                    graph.add_edge((&node, &format!("{},{}", row, column + 1), weight));
+                }
+            }
+        }
+        graph
+    }
+
+    // Cheapest Steiner tree by brute force: the minimum spanning tree of the terminals plus every subset of the other nodes
+    fn cheapest_steiner_tree(graph: &UndirectedGraph, terminals: &[&str]) -> i64 {
+        let others: Vec<&String> = graph
+            .nodes()
+            .into_iter()
+            .filter(|n| !terminals.contains(&n.as_str()))
+            .collect();
+        let mut best = i64::MAX;
+        for mask in 0..(1u32 << others.len()) {
+            let mut nodes: Vec<&str> = terminals.to_vec();
+            nodes.extend((0..others.len()).filter(|i| mask & (1 << i) != 0).map(|i| others[i].as_str()));
+            if let Ok((cost, _)) = minimum_spanning_tree(&graph.induced_subgraph(&nodes).unwrap()) {
+                best = best.min(cost);
+            }
+        }
+        best
+    }
+
+    // Is the result a tree containing every terminal, with only terminals as leaves?
+    fn check_steiner_tree(tree: &UndirectedGraph, terminals: &[&str]) {
+        for terminal in terminals {
+            assert!(tree.contains(terminal));
+        }
+        assert_eq!(tree.edges().len(), 2 * (tree.nodes().len() - 1));
+        assert_eq!(tree.shortest_path_lengths(terminals[0]).unwrap().len(), tree.nodes().len());
+        for node in tree.nodes() {
+            if tree.neighbours(node).unwrap().len() == 1 {
+                assert!(terminals.contains(&node.as_str()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_steiner_tree() {
+        let graph = triangle_with_centre();
+        let terminals = ["a", "b", "c"];
+
+        let (cost, tree) = steiner_tree(&graph, &terminals).unwrap();
+
+        assert_eq!(cost, 10);
+        assert_eq!(tree.nodes().len(), 3);
+        check_steiner_tree(&tree, &terminals);
+        assert_eq!(cheapest_steiner_tree(&graph, &terminals), 9);
+    }
+
+    #[test]
+    fn test_steiner_tree_through_other_nodes() {
+        let graph = grid_graph();
+        let cases: [&[&str]; 4] = [
+            &["0,0", "3,3"],
+            &["0,0", "0,3", "3,0", "3,3"],
+            &["1,1", "2,2", "0,3", "3,1"],
+            &["0,1", "1,0", "1,2", "2,1", "3,3"],
+        ];
+
+        for terminals in cases.iter() {
+            let (cost, tree) = steiner_tree(&graph, terminals).unwrap();
+            check_steiner_tree(&tree, terminals);
+
+            let cheapest = cheapest_steiner_tree(&graph, terminals);
+            assert!(cheapest <= cost && cost <= 2 * cheapest);
+            assert_eq!(cost, tree.edges().iter().map(|(_, _, w)| *w as i64).sum::<i64>() / 2);
+        }
+
+        // With two terminals the tree is a shortest path
+        let (cost, _) = steiner_tree(&graph, &["0,0", "3,3"]).unwrap();
+        assert_eq!(cost, graph.shortest_path_costs("0,0").unwrap()[&String::from("3,3")]);
+    }
+
+    #[test]
+    fn test_small_steiner_trees() {
+        let graph = triangle_with_centre();
+
+        let (cost, tree) = steiner_tree(&graph, &[]).unwrap();
+        assert_eq!(cost, 0);
+        assert!(tree.nodes().is_empty());
+
+        let (cost, tree) = steiner_tree(&graph, &["s", "s"]).unwrap();
+        assert_eq!(cost, 0);
+        assert_eq!(tree.nodes().len(), 1);
+        assert!(tree.contains("s"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut graph = triangle_with_centre();
+        graph.add_node("island");
+
+        assert_eq!(steiner_tree(&graph, &["a", "z"]).err(), Some(GraphError::NodeNotInGraph(String::from("z"))));
+        assert_eq!(steiner_tree(&graph, &["a", "island"]).err(), Some(GraphError::Disconnected));
+
+        graph.add_edge(("island", "a", -1));
+        assert!(matches!(
+            steiner_tree(&graph, &["a", "b"]),
+            Err(GraphError::NegativeWeight { weight: -1, .. })
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+/*
+
+How it works:
+
+Repeatable "random" numbers for tests
+
+A linear congruential generator: each number is the last one times a constant plus another constant
+(wrapping around at 2^64). The same seed always gives the same numbers, so tests built on random
+inputs are repeatable. The low bits of an LCG repeat quickly, so only the high bits are used.
+
+Only included in test builds:
+
+#[cfg(test)]
+#[path = "test_random.rs"]
+mod test_random;
+
+*/
+
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+
+    // A number in 0..n
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.state >> 33) % n
+    }
+}
+
+#[cfg(test)]
+mod test_lcg {
+    use super::*;
+
+    #[test]
+    fn test_below() {
+        let numbers: Vec<u64> = {
+            let mut random = Lcg::new(42);
+            (0..1000).map(|_| random.below(10)).collect()
+        };
+        let mut random = Lcg::new(42);
+        assert!(numbers.iter().all(|n| *n == random.below(10)));
+
+        // Every value in 0..10 turns up
+        assert!((0..10).all(|n| numbers.contains(&n)));
+    }
+}
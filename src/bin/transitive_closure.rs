@@ -0,0 +1,269 @@
+/*
+
+How it works:
+
+Transitive Closure & Transitive Reduction
+
+The transitive closure of a directed graph has an edge a -> c whenever c can be reached from a
+(by following one or more edges). i.e. if a -> b and b -> c, the closure also has a -> c.
+
+The transitive reduction goes the other way: it is the smallest graph with the same reachability.
+An edge a -> c is removed if c can still be reached from a without it (i.e. a -> b -> c).
+The reduction is only unique for a directed acyclic graph (DAG), so graphs with cycles are rejected.
+
+Consider the DAG:
+
+(a, b) (b, c) (a, c) (c, d)
+
+Closure adds:    (a, d) (b, d)
+Reduction drops: (a, c) because a -> b -> c
+
+Reachability is stored as one row of bits per node, so "can a reach b?" is a single lookup:
+
+       a b c d
+    a [0 1 1 1]
+    b [0 0 1 1]
+    c [0 0 0 1]
+    d [0 0 0 0]
+
+*/
+
+// Use the graph types from graph.rs
+#[path = "graph.rs"]
+mod graph;
+
+use graph::{DirectedGraph, Graph, GraphError};
+use std::collections::HashMap;
+
+// Precomputed index answering "can a reach b?" queries
+pub struct Reachability {
+    // Position of each node's row (and bit) in `rows`
+    index: HashMap<String, usize>,
+    // rows[i] holds a bit for every node reachable from node i in one or more steps
+    // Bits are packed 64 per u64
+    rows: Vec<Vec<u64>>,
+}
+
+impl Reachability {
+    pub fn new(graph: &DirectedGraph) -> Reachability {
+        // Sort nodes so that the index is the same on every run
+        let mut nodes: Vec<&String> = graph.nodes().into_iter().collect();
+        nodes.sort();
+        let index: HashMap<String, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.to_string(), i))
+            .collect();
+
+        let words = nodes.len().div_ceil(64);
+        let mut rows = vec![vec![0u64; words]; nodes.len()];
+
+        // Depth first search from every node, setting a bit for each node found
+        for (i, node) in nodes.iter().enumerate() {
+            let mut stack: Vec<&String> = vec![node];
+            while let Some(current) = stack.pop() {
+                for (neighbour, _) in graph.neighbours(current).unwrap() {
+                    let j = index[neighbour];
+                    if rows[i][j / 64] & (1 << (j % 64)) == 0 {
+                        rows[i][j / 64] |= 1 << (j % 64);
+                        stack.push(neighbour);
+                    }
+                }
+            }
+        }
+
+        Reachability { index, rows }
+    }
+
+    fn position(&self, node: &str) -> Result<usize, GraphError> {
+        match self.index.get(node) {
+            None => Err(GraphError::NodeNotInGraph(node.to_string())),
+            Some(i) => Ok(*i),
+        }
+    }
+
+    // Look up bit j in row i
+    fn reaches(&self, i: usize, j: usize) -> bool {
+        self.rows[i][j / 64] & (1 << (j % 64)) != 0
+    }
+
+    // Can b be reached from a? A node can always reach itself
+    pub fn reachable(&self, a: &str, b: &str) -> Result<bool, GraphError> {
+        let i = self.position(a)?;
+        let j = self.position(b)?;
+        Ok(i == j || self.reaches(i, j))
+    }
+
+    // A graph has a cycle if some node can reach itself by following edges
+    pub fn is_acyclic(&self) -> bool {
+        (0..self.rows.len()).all(|i| !self.reaches(i, i))
+    }
+}
+
+// Add an edge (with the given weight) from every node to every node it can reach
+// Edges already in the graph keep their original weight
+pub fn transitive_closure(graph: &DirectedGraph, weight: i32) -> DirectedGraph {
+    let reachability = Reachability::new(graph);
+
+    let mut closure = DirectedGraph::with_policy(graph.edge_policy());
+    for node in graph.nodes() {
+        closure.add_node(node);
+    }
+    for (from_node, to_node, w) in graph.edges() {
+        closure.add_edge((from_node.as_str(), to_node.as_str(), w));
+    }
+    for from_node in graph.nodes() {
+        let i = reachability.index[from_node];
+        for to_node in graph.nodes() {
+            let j = reachability.index[to_node];
+            let has_edge = graph
+                .neighbours(from_node)
+                .unwrap()
+                .iter()
+                .any(|(n, _)| n == to_node);
+            if reachability.reaches(i, j) && !has_edge {
+                closure.add_edge((from_node.as_str(), to_node.as_str(), weight));
+            }
+        }
+    }
+    closure
+}
+
+// Remove every edge a -> c where c can also be reached through another neighbour of a
+// Returns GraphError::NotADag if the graph has a cycle
+// Parallel edges are merged into the first one found
+pub fn transitive_reduction(graph: &DirectedGraph) -> Result<DirectedGraph, GraphError> {
+    let reachability = Reachability::new(graph);
+    if !reachability.is_acyclic() {
+        return Err(GraphError::NotADag);
+    }
+
+    let mut reduction = DirectedGraph::with_policy(graph.edge_policy());
+    for from_node in graph.nodes() {
+        reduction.add_node(from_node);
+        let neighbours = graph.neighbours(from_node).unwrap();
+        for (to_node, weight) in neighbours {
+            let j = reachability.index[to_node];
+
+            // Is to_node reachable through a different neighbour?
+            let redundant = neighbours.iter().any(|(other, _)| {
+                other != to_node && reachability.reaches(reachability.index[other], j)
+            });
+            let duplicate = reduction
+                .neighbours(from_node)
+                .unwrap()
+                .iter()
+                .any(|(n, _)| n == to_node);
+
+            if !redundant && !duplicate {
+                reduction.add_edge((from_node.as_str(), to_node.as_str(), *weight));
+            }
+        }
+    }
+    Ok(reduction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use graph::EdgePolicy;
+
+    fn example_dag() -> DirectedGraph {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "c", 2));
+        graph.add_edge(("a", "c", 3));
+        graph.add_edge(("c", "d", 4));
+        graph
+    }
+
+    #[test]
+    fn test_reachable() {
+        let graph = example_dag();
+        let reachability = Reachability::new(&graph);
+
+        assert!(reachability.reachable("a", "d").unwrap());
+        assert!(reachability.reachable("b", "d").unwrap());
+        assert!(reachability.reachable("d", "d").unwrap());
+        assert!(!reachability.reachable("d", "a").unwrap());
+        assert!(!reachability.reachable("c", "b").unwrap());
+        assert_eq!(
+            reachability.reachable("a", "e"),
+            Err(GraphError::NodeNotInGraph(String::from("e")))
+        );
+        assert!(reachability.is_acyclic());
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        let graph = example_dag();
+
+        let closure = transitive_closure(&graph, 0);
+
+        assert_eq!(closure.edges().len(), 6);
+        assert_eq!(
+            closure.neighbours("a").unwrap(),
+            &vec![
+                (String::from("b"), 1),
+                (String::from("c"), 3),
+                (String::from("d"), 0)
+            ]
+        );
+        assert_eq!(
+            closure.neighbours("b").unwrap(),
+            &vec![(String::from("c"), 2), (String::from("d"), 0)]
+        );
+        assert_eq!(closure.neighbours("d").unwrap(), &vec![]);
+    }
+
+    #[test]
+    fn test_transitive_closure_of_cycle() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "a", 1));
+
+        let closure = transitive_closure(&graph, 0);
+
+        // Each node can reach itself around the cycle
+        assert_eq!(closure.edges().len(), 4);
+        assert!(closure.neighbours("a").unwrap().contains(&(String::from("a"), 0)));
+    }
+
+    #[test]
+    fn test_transitive_reduction() {
+        let mut graph = example_dag();
+        // A parallel edge & an edge implied by a longer path
+        graph.add_edge(("a", "b", 5));
+        graph.add_edge(("a", "d", 6));
+
+        let reduction = transitive_reduction(&graph).unwrap();
+
+        assert_eq!(reduction.edges().len(), 3);
+        assert_eq!(reduction.neighbours("a").unwrap(), &vec![(String::from("b"), 1)]);
+        assert_eq!(reduction.neighbours("b").unwrap(), &vec![(String::from("c"), 2)]);
+        assert_eq!(reduction.neighbours("c").unwrap(), &vec![(String::from("d"), 4)]);
+    }
+
+    #[test]
+    fn test_edge_policy_is_kept() {
+        let mut graph = DirectedGraph::with_policy(EdgePolicy::KeepMinWeight);
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "c", 2));
+
+        let mut closure = transitive_closure(&graph, 9);
+        assert_eq!(closure.edge_policy(), EdgePolicy::KeepMinWeight);
+        // The added edge a -> c is lowered rather than duplicated
+        closure.add_edge(("a", "c", 3));
+        assert_eq!(closure.neighbours("a").unwrap(), &vec![(String::from("b"), 1), (String::from("c"), 3)]);
+
+        assert_eq!(transitive_reduction(&graph).unwrap().edge_policy(), EdgePolicy::KeepMinWeight);
+    }
+
+    #[test]
+    fn test_transitive_reduction_of_cycle() {
+        let mut graph = example_dag();
+        graph.add_edge(("d", "a", 1));
+
+        assert_eq!(transitive_reduction(&graph).err(), Some(GraphError::NotADag));
+    }
+}
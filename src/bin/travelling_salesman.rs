@@ -0,0 +1,558 @@
+/*
+
+How it works:
+
+Travelling Salesman & Hamiltonian Paths / Cycles
+
+A Hamiltonian path visits every node of a graph exactly once. A Hamiltonian cycle also returns to where it started.
+The travelling salesman problem (TSP) asks for the cheapest Hamiltonian cycle (a "tour").
+
+Exact answers (Held-Karp dynamic programming):
+
+Keep a table over every subset of the nodes (stored as the bits of a number, a "mask"):
+
+cost[mask][j] = cheapest path that starts at the start node, visits exactly the nodes in mask & ends at j
+
+1. cost[{j}][j] = weight of the edge start -> j
+2. Extend each path by one edge: cost[mask + k][k] = min(cost[mask][j] + weight of j -> k)
+3. The cheapest tour is the min over j of cost[all nodes][j] + weight of j -> start
+
+This takes O(2^n * n^2) time & O(2^n * n) memory, so graphs are limited to MAX_EXACT_NODES nodes.
+Detecting a Hamiltonian path or cycle uses the same table with "is there a path?" instead of "what does it cost?".
+
+Approximate answers (for larger graphs):
+
+The cost between two nodes is taken as the cost of the shortest route between them (the "metric closure"),
+so a tour can pass through a node on its way to another. Costs then obey the triangle inequality
+(going direct is never more expensive than a detour), which the approximation needs.
+
+1. Find the minimum spanning tree of the closure (with Kruskal's algorithm).
+2. Visit the nodes in the order a depth first walk of the tree first reaches them, then return to the start.
+   Walking the tree uses each tree edge twice & skipping nodes already seen is never more expensive,
+   so the tour costs at most 2 * the tree <= 2 * the best tour.
+3. Improve the tour with 2-opt: if reversing a section of the tour makes it cheaper (i.e. it removes a crossing), reverse it.
+   Repeat until no reversal helps.
+
+Consider the complete graph:
+
+(a, b, 10) (a, c, 15) (a, d, 20)
+(b, c, 35) (b, d, 25) (c, d, 30)
+
+The cheapest tour is a -> b -> d -> c -> a, cost = 80
+
+*/
+
+// Use Kruskal's algorithm from minimum_spanning_tree.rs (& the graph types from graph.rs, which it includes)
+#[path = "minimum_spanning_tree.rs"]
+mod minimum_spanning_tree;
+
+use minimum_spanning_tree::graph::{Graph, GraphError, UndirectedGraph};
+use minimum_spanning_tree::{try_kruskal, Edge};
+
+// Largest graph accepted by the exact (exponential time) functions
+pub const MAX_EXACT_NODES: usize = 20;
+
+// A tour is the total cost & the list of nodes it visits (starting & ending at the same node)
+pub type Tour = (i64, Vec<String>);
+
+// Sorted node names & the cheapest direct edge between each pair (self loops are ignored)
+struct CostMatrix {
+    names: Vec<String>,
+    costs: Vec<Vec<Option<i64>>>,
+}
+
+impl CostMatrix {
+    fn new<G: Graph>(graph: &G) -> Result<CostMatrix, GraphError> {
+        let mut names: Vec<String> = graph.nodes().into_iter().cloned().collect();
+        names.sort();
+        if names.is_empty() {
+            return Err(GraphError::EmptyGraph);
+        }
+        if names.len() > MAX_EXACT_NODES {
+            return Err(GraphError::TooManyNodes {
+                nodes: names.len(),
+                limit: MAX_EXACT_NODES,
+            });
+        }
+
+        let mut costs = vec![vec![None; names.len()]; names.len()];
+        for (i, from_node) in names.iter().enumerate() {
+            for (to_node, weight) in graph.neighbours(from_node).unwrap() {
+                let j = names.binary_search(to_node).unwrap();
+                let weight = *weight as i64;
+                if i != j && costs[i][j].is_none_or(|c| weight < c) {
+                    costs[i][j] = Some(weight);
+                }
+            }
+        }
+        Ok(CostMatrix { names, costs })
+    }
+
+    fn position(&self, node: &str) -> Result<usize, GraphError> {
+        match self.names.binary_search(&node.to_string()) {
+            Err(_) => Err(GraphError::NodeNotInGraph(node.to_string())),
+            Ok(i) => Ok(i),
+        }
+    }
+
+    // adjacent[i] has bit j set if there is an edge i -> j
+    fn adjacent(&self) -> Vec<u32> {
+        self.costs
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, c)| c.is_some())
+                    .fold(0, |bits, (j, _)| bits | 1 << j)
+            })
+            .collect()
+    }
+
+    fn to_names(&self, route: &[usize]) -> Vec<String> {
+        route.iter().map(|i| self.names[*i].clone()).collect()
+    }
+}
+
+// Iterate over the positions of the set bits of a mask
+fn bits(mask: u32) -> impl Iterator<Item = usize> {
+    (0..32).filter(move |j| mask & (1 << j) != 0)
+}
+
+// reach[mask] has bit j set if some path visits exactly the nodes in mask & ends at j
+// With a start node, only paths beginning at the start are counted
+fn reach_table(adjacent: &[u32], start: Option<usize>) -> Vec<u32> {
+    let n = adjacent.len();
+    let mut reach = vec![0u32; 1 << n];
+    match start {
+        Some(s) => reach[1 << s] = 1 << s,
+        None => (0..n).for_each(|j| reach[1 << j] = 1 << j),
+    }
+    for mask in 1..reach.len() {
+        for j in bits(reach[mask]) {
+            for k in bits(adjacent[j] & !(mask as u32)) {
+                reach[mask | 1 << k] |= 1 << k;
+            }
+        }
+    }
+    reach
+}
+
+// Rebuild a path (found by reach_table) that visits the nodes in mask & ends at end
+fn walk_back(adjacent: &[u32], reach: &[u32], mut mask: usize, mut end: usize) -> Vec<usize> {
+    let mut path = vec![end];
+    while mask.count_ones() > 1 {
+        mask ^= 1 << end;
+        end = bits(reach[mask]).find(|i| adjacent[*i] & (1 << end) != 0).unwrap();
+        path.push(end);
+    }
+    path.reverse();
+    path
+}
+
+// A path visiting every node exactly once, or None if there isn't one
+pub fn hamiltonian_path<G: Graph>(graph: &G) -> Result<Option<Vec<String>>, GraphError> {
+    let matrix = CostMatrix::new(graph)?;
+    let adjacent = matrix.adjacent();
+    let reach = reach_table(&adjacent, None);
+
+    let all = reach.len() - 1;
+    Ok(bits(reach[all])
+        .next()
+        .map(|end| matrix.to_names(&walk_back(&adjacent, &reach, all, end))))
+}
+
+// A cycle visiting every node exactly once (starting & ending at the first node by name), or None if there isn't one
+// With two nodes the cycle goes there & back along the same (undirected) edge
+pub fn hamiltonian_cycle<G: Graph>(graph: &G) -> Result<Option<Vec<String>>, GraphError> {
+    let matrix = CostMatrix::new(graph)?;
+    if matrix.names.len() == 1 {
+        return Ok(Some(matrix.to_names(&[0, 0])));
+    }
+    let adjacent = matrix.adjacent();
+    let reach = reach_table(&adjacent, Some(0));
+
+    let all = reach.len() - 1;
+    Ok(bits(reach[all])
+        .find(|j| adjacent[*j] & 1 != 0)
+        .map(|end| {
+            let mut cycle = walk_back(&adjacent, &reach, all, end);
+            cycle.push(0);
+            matrix.to_names(&cycle)
+        }))
+}
+
+// The cheapest tour from start (following edges of the graph), or None if there is no Hamiltonian cycle
+pub fn held_karp<G: Graph>(graph: &G, start: &str) -> Result<Option<Tour>, GraphError> {
+    let matrix = CostMatrix::new(graph)?;
+    let s = matrix.position(start)?;
+    if matrix.names.len() == 1 {
+        return Ok(Some((0, matrix.to_names(&[s, s]))));
+    }
+
+    // The table only covers the other nodes (every path begins at start)
+    let others: Vec<usize> = (0..matrix.names.len()).filter(|i| *i != s).collect();
+    let m = others.len();
+    let cost_between = |a: usize, b: usize| matrix.costs[a][b];
+
+    // cost[mask * m + j] & the node before j on that path (m means start)
+    let mut cost = vec![i64::MAX; (1 << m) * m];
+    let mut previous = vec![m as u8; (1 << m) * m];
+
+    // 1. Paths of a single edge from start
+    for (j, node) in others.iter().enumerate() {
+        if let Some(c) = cost_between(s, *node) {
+            cost[(1 << j) * m + j] = c;
+        }
+    }
+
+    // 2. Extend each path by one edge (masks only grow, so smaller masks are finished first)
+    for mask in 1..(1usize << m) {
+        for j in bits(mask as u32) {
+            let current = cost[mask * m + j];
+            if current == i64::MAX {
+                continue;
+            }
+            for k in (0..m).filter(|k| mask & (1 << k) == 0) {
+                if let Some(c) = cost_between(others[j], others[k]) {
+                    let next = (mask | 1 << k) * m + k;
+                    if current + c < cost[next] {
+                        cost[next] = current + c;
+                        previous[next] = j as u8;
+                    }
+                }
+            }
+        }
+    }
+
+    // 3. Close the cheapest path back to start
+    let all = (1 << m) - 1;
+    let best = (0..m)
+        .filter(|j| cost[all * m + j] != i64::MAX)
+        .filter_map(|j| cost_between(others[j], s).map(|c| (cost[all * m + j] + c, j)))
+        .min();
+
+    Ok(best.map(|(total, mut j)| {
+        let mut route = vec![s];
+        let mut mask = all;
+        while j != m {
+            route.push(others[j]);
+            let before = previous[mask * m + j] as usize;
+            mask ^= 1 << j;
+            j = before;
+        }
+        route.push(s);
+        route.reverse();
+        (total, matrix.to_names(&route))
+    }))
+}
+
+// Sorted node names & the cost of the shortest route between every pair of nodes
+fn metric_closure(graph: &UndirectedGraph) -> Result<(Vec<String>, Vec<Vec<i64>>), GraphError> {
+    let mut names: Vec<String> = graph.nodes().into_iter().cloned().collect();
+    names.sort();
+    if names.is_empty() {
+        return Err(GraphError::EmptyGraph);
+    }
+
+    let mut distances = Vec::with_capacity(names.len());
+    for node in names.iter() {
+        let costs = graph.shortest_path_costs(node)?;
+        if costs.len() < names.len() {
+            return Err(GraphError::Disconnected);
+        }
+        distances.push(names.iter().map(|n| costs[n]).collect());
+    }
+    Ok((names, distances))
+}
+
+fn route_cost(route: &[usize], distances: &[Vec<i64>]) -> i64 {
+    route.windows(2).map(|pair| distances[pair[0]][pair[1]]).sum()
+}
+
+// Reverse sections of the route (keeping the first & last node in place) while that makes it cheaper
+fn improve(route: &mut [usize], distances: &[Vec<i64>]) {
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for i in 1..route.len().saturating_sub(2) {
+            for k in i + 1..route.len() - 1 {
+                let (a, b, c, d) = (route[i - 1], route[i], route[k], route[k + 1]);
+                // Swap edges a-b & c-d for a-c & b-d
+                if distances[a][c] + distances[b][d] < distances[a][b] + distances[c][d] {
+                    route[i..=k].reverse();
+                    improved = true;
+                }
+            }
+        }
+    }
+}
+
+// A tour from start costing at most twice the cheapest tour, from a depth first walk of the minimum spanning tree
+// Consecutive nodes are joined by the shortest route between them (which may pass through other nodes)
+pub fn mst_tour(graph: &UndirectedGraph, start: &str) -> Result<Tour, GraphError> {
+    let (names, distances) = metric_closure(graph)?;
+    let s = match names.binary_search(&start.to_string()) {
+        Err(_) => return Err(GraphError::NodeNotInGraph(start.to_string())),
+        Ok(s) => s,
+    };
+
+    // 1. Minimum spanning tree of the closure
+    let mut edges = Vec::new();
+    for (i, row) in distances.iter().enumerate() {
+        for (j, cost) in row.iter().enumerate().skip(i + 1) {
+            edges.push(Edge::new(i as i64, j as i64, *cost));
+        }
+    }
+    let (_, tree_edges) = try_kruskal(edges, names.len() as i64)?;
+    let mut tree = vec![Vec::new(); names.len()];
+    for edge in tree_edges.iter() {
+        tree[edge.source as usize].push(edge.destination as usize);
+        tree[edge.destination as usize].push(edge.source as usize);
+    }
+
+    // 2. Depth first walk, visiting neighbours in name order
+    let mut route = Vec::with_capacity(names.len() + 1);
+    let mut visited = vec![false; names.len()];
+    let mut stack = vec![s];
+    while let Some(node) = stack.pop() {
+        if visited[node] {
+            continue;
+        }
+        visited[node] = true;
+        route.push(node);
+        tree[node].sort_unstable();
+        stack.extend(tree[node].iter().rev().filter(|n| !visited[**n]));
+    }
+    route.push(s);
+
+    let names = route.iter().map(|i| names[*i].clone()).collect();
+    Ok((route_cost(&route, &distances), names))
+}
+
+// Improve a tour (starting & ending at the same node) with 2-opt, keeping the same start
+// Costs are shortest routes between consecutive nodes, as in mst_tour
+pub fn two_opt(graph: &UndirectedGraph, tour: &[String]) -> Result<Tour, GraphError> {
+    let (names, distances) = metric_closure(graph)?;
+    let mut route = Vec::with_capacity(tour.len());
+    for node in tour {
+        match names.binary_search(node) {
+            Err(_) => return Err(GraphError::NodeNotInGraph(node.to_string())),
+            Ok(i) => route.push(i),
+        }
+    }
+
+    improve(&mut route, &distances);
+
+    let names = route.iter().map(|i| names[*i].clone()).collect();
+    Ok((route_cost(&route, &distances), names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minimum_spanning_tree::graph::DirectedGraph;
+
+    fn example_graph() -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        graph.add_edge(("a", "b", 10));
+        graph.add_edge(("a", "c", 15));
+        graph.add_edge(("a", "d", 20));
+        graph.add_edge(("b", "c", 35));
+        graph.add_edge(("b", "d", 25));
+        graph.add_edge(("c", "d", 30));
+        graph
+    }
+
+    // The Petersen graph has a Hamiltonian path but no Hamiltonian cycle
+    fn petersen_graph() -> UndirectedGraph {
+        let mut graph = UndirectedGraph::new();
+        for i in 0..5 {
+            graph.add_edge((&i.to_string(), &((i + 1) % 5).to_string(), 1));
+            graph.add_edge((&i.to_string(), &(i + 5).to_string(), 1));
+            graph.add_edge((&(i + 5).to_string(), &((i + 2) % 5 + 5).to_string(), 1));
+        }
+        graph
+    }
+
+    // Nodes on a grid, with every pair joined by an edge weighted by their (Manhattan) distance
+    fn grid_graph() -> UndirectedGraph {
+        let points: [(i32, i32); 9] = [(0, 0), (4, 1), (2, 5), (7, 3), (1, 8), (6, 7), (9, 9), (3, 2), (8, 0)];
+        let mut graph = UndirectedGraph::new();
+        for (i, &(x1, y1)) in points.iter().enumerate() {
+            for (j, &(x2, y2)) in points.iter().enumerate().skip(i + 1) {
+                let weight: i32 = (x1 - x2).abs() + (y1 - y2).abs();
+                graph.add_edge((&i.to_string(), &j.to_string(), weight));
+            }
+        }
+        graph
+    }
+
+    // Does the list visit every node of the graph exactly once, following edges?
+    fn is_hamiltonian_path<G: Graph>(graph: &G, path: &[String]) -> bool {
+        let mut sorted = path.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        sorted.len() == graph.nodes().len()
+            && path.len() == graph.nodes().len()
+            && path
+                .windows(2)
+                .all(|pair| graph.neighbours(&pair[0]).unwrap().iter().any(|(n, _)| *n == pair[1]))
+    }
+
+    #[test]
+    fn test_held_karp() {
+        let graph = example_graph();
+
+        let (cost, tour) = held_karp(&graph, "a").unwrap().unwrap();
+        assert_eq!(cost, 80);
+        assert_eq!(tour.first(), Some(&String::from("a")));
+        assert_eq!(tour.last(), Some(&String::from("a")));
+        assert!(is_hamiltonian_path(&graph, &tour[..4]));
+
+        // The same tour, from a different start
+        let (cost, tour) = held_karp(&graph, "c").unwrap().unwrap();
+        assert_eq!(cost, 80);
+        assert_eq!(tour.len(), 5);
+        assert_eq!(tour.first(), Some(&String::from("c")));
+    }
+
+    #[test]
+    fn test_held_karp_directed() {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "c", 1));
+        graph.add_edge(("c", "a", 1));
+        graph.add_edge(("a", "c", 5));
+        graph.add_edge(("c", "b", 5));
+        graph.add_edge(("b", "a", 5));
+
+        assert_eq!(
+            held_karp(&graph, "a").unwrap(),
+            Some((3, vec![String::from("a"), String::from("b"), String::from("c"), String::from("a")]))
+        );
+
+        // Without c -> a there is no way back to a
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "c", 1));
+        assert_eq!(held_karp(&graph, "a").unwrap(), None);
+    }
+
+    #[test]
+    fn test_small_tours() {
+        let mut graph = UndirectedGraph::new();
+        graph.add_node("a");
+        assert_eq!(held_karp(&graph, "a").unwrap(), Some((0, vec![String::from("a"), String::from("a")])));
+        assert_eq!(hamiltonian_cycle(&graph).unwrap(), Some(vec![String::from("a"), String::from("a")]));
+
+        graph.add_edge(("a", "b", 4));
+        assert_eq!(
+            held_karp(&graph, "b").unwrap(),
+            Some((8, vec![String::from("b"), String::from("a"), String::from("b")]))
+        );
+    }
+
+    #[test]
+    fn test_hamiltonian_path() {
+        let graph = petersen_graph();
+        let path = hamiltonian_path(&graph).unwrap().unwrap();
+        assert!(is_hamiltonian_path(&graph, &path));
+
+        let mut line = UndirectedGraph::new();
+        line.add_edge(("a", "b", 1));
+        line.add_edge(("b", "c", 1));
+        line.add_edge(("c", "d", 1));
+        let path = hamiltonian_path(&line).unwrap().unwrap();
+        assert!(is_hamiltonian_path(&line, &path));
+
+        // A star has no Hamiltonian path
+        let mut star = UndirectedGraph::new();
+        star.add_edge(("a", "b", 1));
+        star.add_edge(("a", "c", 1));
+        star.add_edge(("a", "d", 1));
+        assert_eq!(hamiltonian_path(&star).unwrap(), None);
+    }
+
+    #[test]
+    fn test_hamiltonian_cycle() {
+        assert_eq!(hamiltonian_cycle(&petersen_graph()).unwrap(), None);
+
+        let graph = example_graph();
+        let cycle = hamiltonian_cycle(&graph).unwrap().unwrap();
+        assert_eq!(cycle.len(), 5);
+        assert_eq!(cycle.first(), cycle.last());
+        assert!(is_hamiltonian_path(&graph, &cycle[..4]));
+        assert!(is_hamiltonian_path(&graph, &cycle[1..]));
+
+        // A directed cycle can only be followed one way
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("a", "c", 1));
+        graph.add_edge(("c", "b", 1));
+        graph.add_edge(("b", "a", 1));
+        assert_eq!(
+            hamiltonian_cycle(&graph).unwrap(),
+            Some(vec![String::from("a"), String::from("c"), String::from("b"), String::from("a")])
+        );
+    }
+
+    #[test]
+    fn test_approximate_tours() {
+        let graph = grid_graph();
+        let (best, _) = held_karp(&graph, "0").unwrap().unwrap();
+
+        let (mst_cost, tour) = mst_tour(&graph, "0").unwrap();
+        assert_eq!(tour.len(), 10);
+        assert!(is_hamiltonian_path(&graph, &tour[..9]));
+        assert_eq!(tour.first(), tour.last());
+        assert!(best <= mst_cost && mst_cost <= 2 * best);
+
+        let (improved_cost, improved) = two_opt(&graph, &tour).unwrap();
+        assert!(is_hamiltonian_path(&graph, &improved[..9]));
+        assert_eq!(improved.first(), Some(&String::from("0")));
+        assert_eq!(improved.last(), Some(&String::from("0")));
+        assert!(best <= improved_cost && improved_cost <= mst_cost);
+    }
+
+    #[test]
+    fn test_mst_tour_uses_shortest_routes() {
+        // Not every pair is joined by an edge, so the tour returns to a through b & c
+        let mut graph = UndirectedGraph::new();
+        graph.add_edge(("a", "b", 1));
+        graph.add_edge(("b", "c", 2));
+        graph.add_edge(("c", "d", 3));
+
+        let (cost, tour) = mst_tour(&graph, "a").unwrap();
+        assert_eq!(cost, 12);
+        assert_eq!(tour, vec!["a", "b", "c", "d", "a"]);
+
+        graph.add_node("e");
+        assert_eq!(mst_tour(&graph, "a"), Err(GraphError::Disconnected));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut graph = UndirectedGraph::new();
+        assert_eq!(held_karp(&graph, "a"), Err(GraphError::EmptyGraph));
+        assert_eq!(mst_tour(&graph, "a"), Err(GraphError::EmptyGraph));
+
+        graph.add_edge(("a", "b", 1));
+        assert_eq!(held_karp(&graph, "z"), Err(GraphError::NodeNotInGraph(String::from("z"))));
+        assert_eq!(mst_tour(&graph, "z"), Err(GraphError::NodeNotInGraph(String::from("z"))));
+        assert_eq!(
+            two_opt(&graph, &[String::from("a"), String::from("z")]),
+            Err(GraphError::NodeNotInGraph(String::from("z")))
+        );
+
+        graph.add_edge(("b", "c", -1));
+        assert!(matches!(mst_tour(&graph, "a"), Err(GraphError::NegativeWeight { weight: -1, .. })));
+
+        let mut large = UndirectedGraph::new();
+        for i in 0..=MAX_EXACT_NODES {
+            large.add_node(&i.to_string());
+        }
+        let too_many = Err(GraphError::TooManyNodes { nodes: 21, limit: 20 });
+        assert_eq!(hamiltonian_path(&large), too_many);
+        assert_eq!(hamiltonian_cycle(&large), too_many);
+        assert_eq!(held_karp(&large, "0").map(|_| ()), Err(GraphError::TooManyNodes { nodes: 21, limit: 20 }));
+    }
+}
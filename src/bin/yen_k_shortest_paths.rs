@@ -0,0 +1,271 @@
+/*
+
+How it works:
+
+Yen's K Shortest (Loopless) Paths
+
+Finds the k cheapest paths between two nodes of a directed graph, where no path visits the same node twice.
+
+1. Find the shortest path with Dijkstra's algorithm. This is the 1st path.
+2. To find path k + 1, take path k & treat every node on it (except the target) as a "spur" node:
+   * The "root" path is the start of path k up to the spur node.
+   * Remove every edge leaving the spur node that an already found path with the same root uses
+     (so we can't just find one of those paths again).
+   * Remove the nodes of the root path (except the spur node) so that the new path is loopless.
+   * Find the shortest path from the spur node to the target in what's left of the graph.
+   * root path + spur path is a candidate for path k + 1.
+3. The cheapest candidate (that hasn't already been chosen) is path k + 1.
+4. Stop after k paths or when there are no candidates left.
+
+Consider the graph:
+
+(C, D, 3) (C, E, 2) (D, F, 4) (E, D, 1) (E, F, 2)
+(E, G, 3) (F, G, 2) (F, H, 1) (G, H, 2)
+
+The 3 shortest paths from C to H are:
+
+C -> E -> F -> H, cost = 5
+C -> E -> G -> H, cost = 7
+C -> D -> F -> H, cost = 8
+
+Edge weights must not be negative (Dijkstra's algorithm assumes that adding an edge never makes a path cheaper),
+so graphs with a negative edge are rejected with GraphError::NegativeWeight.
+
+*/
+
+// Use the graph types from graph.rs
+#[path = "graph.rs"]
+mod graph;
+
+use graph::{DirectedGraph, Graph, GraphError};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+// A path is the total cost & the list of nodes that it visits (including source & target)
+pub type Path = (i64, Vec<String>);
+
+// Cheapest weight of any edge from -> to (there may be parallel edges)
+fn edge_cost(graph: &DirectedGraph, from_node: &str, to_node: &str) -> Option<i64> {
+    graph
+        .neighbours(from_node)
+        .ok()?
+        .iter()
+        .filter(|(n, _)| n == to_node)
+        .map(|(_, weight)| *weight as i64)
+        .min()
+}
+
+// Dijkstra's algorithm, skipping the removed nodes & edges
+fn dijkstra(
+    graph: &DirectedGraph,
+    source: &str,
+    target: &str,
+    removed_nodes: &HashSet<&str>,
+    removed_edges: &HashSet<(&str, &str)>,
+) -> Option<Path> {
+    let mut distances: HashMap<&str, i64> = HashMap::new();
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+
+    // BinaryHeap is a max heap, so wrap entries in Reverse to pop the cheapest first
+    // Ties are broken by node name so that results are the same on every run
+    let mut heap = BinaryHeap::new();
+    distances.insert(source, 0);
+    heap.push(Reverse((0, source)));
+
+    while let Some(Reverse((distance, node))) = heap.pop() {
+        if node == target {
+            // Walk back along the previous nodes to rebuild the path
+            let mut path = vec![target.to_string()];
+            let mut current = target;
+            while let Some(p) = previous.get(current) {
+                path.push(p.to_string());
+                current = p;
+            }
+            path.reverse();
+            return Some((distance, path));
+        }
+        // A cheaper route to this node has already been handled
+        if distance > distances[node] {
+            continue;
+        }
+        for (neighbour, weight) in graph.neighbours(node).unwrap() {
+            let neighbour = neighbour.as_str();
+            if removed_nodes.contains(neighbour) || removed_edges.contains(&(node, neighbour)) {
+                continue;
+            }
+            let new_distance = distance + *weight as i64;
+            let is_shorter = match distances.get(neighbour) {
+                None => true,
+                Some(d) => new_distance < *d,
+            };
+            if is_shorter {
+                distances.insert(neighbour, new_distance);
+                previous.insert(neighbour, node);
+                heap.push(Reverse((new_distance, neighbour)));
+            }
+        }
+    }
+    None
+}
+
+// Return up to k loopless paths from source to target, cheapest first
+pub fn k_shortest_paths(
+    graph: &DirectedGraph,
+    source: &str,
+    target: &str,
+    k: usize,
+) -> Result<Vec<Path>, GraphError> {
+    for node in [source, target].iter() {
+        if !graph.contains(node) {
+            return Err(GraphError::NodeNotInGraph(node.to_string()));
+        }
+    }
+    for (from_node, to_node, weight) in graph.edges() {
+        if weight < 0 {
+            return Err(GraphError::NegativeWeight {
+                from: from_node.to_string(),
+                to: to_node.to_string(),
+                weight,
+            });
+        }
+    }
+
+    let mut paths: Vec<Path> = Vec::new();
+    if k == 0 {
+        return Ok(paths);
+    }
+    match dijkstra(graph, source, target, &HashSet::new(), &HashSet::new()) {
+        None => return Ok(paths),
+        Some(path) => paths.push(path),
+    }
+
+    // Candidates for the next path, ordered by (cost, nodes)
+    let mut candidates: BinaryHeap<Reverse<Path>> = BinaryHeap::new();
+
+    while paths.len() < k {
+        let (_, previous_path) = paths.last().unwrap().clone();
+
+        for i in 0..previous_path.len() - 1 {
+            let spur_node = previous_path[i].as_str();
+            let root_path = &previous_path[..=i];
+
+            // Remove the next edge of every found path that shares this root
+            let mut removed_edges: HashSet<(&str, &str)> = HashSet::new();
+            for (_, path) in &paths {
+                if path.len() > i + 1 && &path[..=i] == root_path {
+                    removed_edges.insert((path[i].as_str(), path[i + 1].as_str()));
+                }
+            }
+
+            // Remove the root path so the spur path can't loop back through it
+            let removed_nodes: HashSet<&str> =
+                root_path[..i].iter().map(|n| n.as_str()).collect();
+
+            if let Some((spur_cost, spur_path)) =
+                dijkstra(graph, spur_node, target, &removed_nodes, &removed_edges)
+            {
+                let root_cost: i64 = root_path
+                    .windows(2)
+                    .map(|pair| edge_cost(graph, &pair[0], &pair[1]).unwrap())
+                    .sum();
+
+                let mut total_path: Vec<String> = root_path[..i].to_vec();
+                total_path.extend(spur_path);
+                let candidate = Reverse((root_cost + spur_cost, total_path));
+
+                // The same candidate can be found from several spur nodes
+                if !candidates.iter().any(|c| *c == candidate) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+
+        match candidates.pop() {
+            None => break,
+            Some(Reverse(path)) => paths.push(path),
+        }
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(nodes: &[&str]) -> Vec<String> {
+        nodes.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn example_graph() -> DirectedGraph {
+        let mut graph = DirectedGraph::new();
+        graph.add_edge(("C", "D", 3));
+        graph.add_edge(("C", "E", 2));
+        graph.add_edge(("D", "F", 4));
+        graph.add_edge(("E", "D", 1));
+        graph.add_edge(("E", "F", 2));
+        graph.add_edge(("E", "G", 3));
+        graph.add_edge(("F", "G", 2));
+        graph.add_edge(("F", "H", 1));
+        graph.add_edge(("G", "H", 2));
+        graph
+    }
+
+    #[test]
+    fn test_three_shortest_paths() {
+        let graph = example_graph();
+
+        let paths = k_shortest_paths(&graph, "C", "H", 3).unwrap();
+
+        assert_eq!(
+            paths,
+            vec![
+                (5, path(&["C", "E", "F", "H"])),
+                (7, path(&["C", "E", "G", "H"])),
+                (8, path(&["C", "D", "F", "H"])),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fewer_paths_than_k() {
+        let graph = example_graph();
+
+        // Every loopless path from C to H
+        let paths = k_shortest_paths(&graph, "C", "H", 100).unwrap();
+        assert_eq!(paths.len(), 7);
+
+        // Costs are in ascending order & no path is repeated
+        for i in 1..paths.len() {
+            assert!(paths[i - 1].0 <= paths[i].0);
+            assert!(!paths[..i].contains(&paths[i]));
+        }
+    }
+
+    #[test]
+    fn test_no_path() {
+        let graph = example_graph();
+
+        assert_eq!(k_shortest_paths(&graph, "H", "C", 3).unwrap(), vec![]);
+        assert_eq!(k_shortest_paths(&graph, "C", "H", 0).unwrap(), vec![]);
+        assert_eq!(
+            k_shortest_paths(&graph, "C", "Z", 3),
+            Err(GraphError::NodeNotInGraph(String::from("Z")))
+        );
+    }
+
+    #[test]
+    fn test_negative_weight() {
+        let mut graph = example_graph();
+        graph.add_edge(("D", "E", -1));
+
+        assert_eq!(
+            k_shortest_paths(&graph, "C", "H", 3),
+            Err(GraphError::NegativeWeight {
+                from: String::from("D"),
+                to: String::from("E"),
+                weight: -1
+            })
+        );
+    }
+}
@@ -0,0 +1,4 @@
+// Code shared by several binaries in src/bin (one copy, so they all use the same types)
+
+// Counting & tracing what the sorts do
+pub mod sort_instrumentation;
@@ -0,0 +1,91 @@
+/*
+
+How it works:
+
+Sort Instrumentation
+
+Each sort reports what it does as a list of events:
+
+Compare(i, j): compared the elements at positions i & j
+Swap(i, j):    swapped the elements at positions i & j
+Pass:          started another pass over the array (what a pass is depends on the sort)
+
+Instrumentation counts the events & can also keep them in order (a trace). Replaying the swaps
+of a trace on a copy of the original array gives every step of the sort, which is useful for teaching
+(see sort_visualiser.rs) & for spotting inputs that make a sort do far more work than usual.
+
+This file is part of the library (src/lib.rs) rather than a binary, so that every sort, sorter.rs &
+sort_visualiser.rs share the same SortEvent & Instrumentation types:
+
+use rust_dsa::sort_instrumentation::Instrumentation;
+bubble_sort_instrumented(&mut arr, &mut instrumentation)
+
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortEvent {
+    Compare(usize, usize),
+    Swap(usize, usize),
+    Pass,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Instrumentation {
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub passes: usize,
+    // Every event in order (None if only counting)
+    trace: Option<Vec<SortEvent>>,
+}
+
+impl Instrumentation {
+    // Count events without keeping a trace
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Count events & keep a trace of them
+    pub fn with_trace() -> Self {
+        Self {
+            trace: Some(Vec::new()),
+            ..Self::default()
+        }
+    }
+
+    pub fn record(&mut self, event: SortEvent) {
+        match event {
+            SortEvent::Compare(_, _) => self.comparisons += 1,
+            SortEvent::Swap(_, _) => self.swaps += 1,
+            SortEvent::Pass => self.passes += 1,
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(event);
+        }
+    }
+
+    pub fn trace(&self) -> Option<&[SortEvent]> {
+        self.trace.as_deref()
+    }
+}
+
+#[cfg(test)]
+mod test_sort_instrumentation {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let events = [SortEvent::Pass, SortEvent::Compare(0, 1), SortEvent::Swap(0, 1), SortEvent::Compare(1, 2)];
+
+        let mut counts = Instrumentation::new();
+        let mut traced = Instrumentation::with_trace();
+        for event in events.iter() {
+            counts.record(*event);
+            traced.record(*event);
+        }
+
+        assert_eq!((counts.comparisons, counts.swaps, counts.passes), (2, 1, 1));
+        assert_eq!(counts.trace(), None);
+        assert_eq!((traced.comparisons, traced.swaps, traced.passes), (2, 1, 1));
+        assert_eq!(traced.trace(), Some(&events[..]));
+    }
+}